@@ -1,3 +1,8 @@
+pub mod material;
+pub mod mesh;
+pub mod project;
+pub mod scene;
+
 #[derive(Component)]
 pub struct SelectedEntity;
 
@@ -0,0 +1,226 @@
+use bevy::pbr::ParallaxMappingMethod;
+use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+use serde::{Deserialize, Serialize};
+
+/// Serializable state of a `StandardMaterial`, stored as RON in the material asset source.
+/// Textures are stored as paths relative to the assets folder (with optional `#label`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct MaterialData {
+    pub base_color: Color,
+    pub base_color_texture: Option<String>,
+    pub emissive: Color,
+    pub emissive_texture: Option<String>,
+    pub perceptual_roughness: f32,
+    pub metallic: f32,
+    pub metallic_roughness_texture: Option<String>,
+    pub reflectance: f32,
+    pub normal_map_texture: Option<String>,
+    pub flip_normal_map_y: bool,
+    pub occlusion_texture: Option<String>,
+    pub double_sided: bool,
+    pub cull_mode: Option<FaceData>,
+    pub unlit: bool,
+    pub fog_enabled: bool,
+    pub alpha_mode: AlphaModeData,
+    pub depth_bias: f32,
+    pub depth_map: Option<String>,
+    pub parallax_depth_scale: f32,
+    pub parallax_mapping_method: ParallaxMappingData,
+    pub max_parallax_layer_count: f32,
+}
+
+/// Serializable state of a 2D `ColorMaterial`, stored as RON in the material asset source.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ColorMaterialData {
+    pub color: Color,
+    pub texture: Option<String>,
+}
+
+// mirrors of bevy types, that are not serializable
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum FaceData {
+    Front,
+    Back,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum AlphaModeData {
+    Opaque,
+    Mask(f32),
+    Blend,
+    Premultiplied,
+    Add,
+    Multiply,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ParallaxMappingData {
+    Occlusion,
+    Relief { max_steps: u32 },
+}
+
+impl Default for MaterialData {
+    fn default() -> Self {
+        Self::from_material(&StandardMaterial::default(), |_| None)
+    }
+}
+
+impl MaterialData {
+    /// `texture_path` returns the path of a texture, textures without a path are not stored.
+    pub fn from_material(
+        material: &StandardMaterial,
+        mut texture_path: impl FnMut(&Handle<Image>) -> Option<String>,
+    ) -> Self {
+        let mut texture =
+            |handle: &Option<Handle<Image>>| handle.as_ref().and_then(&mut texture_path);
+        Self {
+            base_color: material.base_color,
+            base_color_texture: texture(&material.base_color_texture),
+            emissive: material.emissive,
+            emissive_texture: texture(&material.emissive_texture),
+            perceptual_roughness: material.perceptual_roughness,
+            metallic: material.metallic,
+            metallic_roughness_texture: texture(&material.metallic_roughness_texture),
+            reflectance: material.reflectance,
+            normal_map_texture: texture(&material.normal_map_texture),
+            flip_normal_map_y: material.flip_normal_map_y,
+            occlusion_texture: texture(&material.occlusion_texture),
+            double_sided: material.double_sided,
+            cull_mode: material.cull_mode.map(|face| match face {
+                Face::Front => FaceData::Front,
+                Face::Back => FaceData::Back,
+            }),
+            unlit: material.unlit,
+            fog_enabled: material.fog_enabled,
+            alpha_mode: match material.alpha_mode {
+                AlphaMode::Opaque => AlphaModeData::Opaque,
+                AlphaMode::Mask(cutoff) => AlphaModeData::Mask(cutoff),
+                AlphaMode::Blend => AlphaModeData::Blend,
+                AlphaMode::Premultiplied => AlphaModeData::Premultiplied,
+                AlphaMode::Add => AlphaModeData::Add,
+                AlphaMode::Multiply => AlphaModeData::Multiply,
+            },
+            depth_bias: material.depth_bias,
+            depth_map: texture(&material.depth_map),
+            parallax_depth_scale: material.parallax_depth_scale,
+            parallax_mapping_method: match material.parallax_mapping_method {
+                ParallaxMappingMethod::Occlusion => ParallaxMappingData::Occlusion,
+                ParallaxMappingMethod::Relief { max_steps } => {
+                    ParallaxMappingData::Relief { max_steps }
+                }
+            },
+            max_parallax_layer_count: material.max_parallax_layer_count,
+        }
+    }
+
+    pub fn texture_paths(&self) -> Vec<String> {
+        [
+            &self.base_color_texture,
+            &self.emissive_texture,
+            &self.metallic_roughness_texture,
+            &self.normal_map_texture,
+            &self.occlusion_texture,
+            &self.depth_map,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
+
+    /// `load_texture` returns the handle of a texture by its path.
+    pub fn to_material(
+        &self,
+        mut load_texture: impl FnMut(&str) -> Handle<Image>,
+    ) -> StandardMaterial {
+        let mut texture = |path: &Option<String>| path.as_deref().map(&mut load_texture);
+        StandardMaterial {
+            base_color: self.base_color,
+            base_color_texture: texture(&self.base_color_texture),
+            emissive: self.emissive,
+            emissive_texture: texture(&self.emissive_texture),
+            perceptual_roughness: self.perceptual_roughness,
+            metallic: self.metallic,
+            metallic_roughness_texture: texture(&self.metallic_roughness_texture),
+            reflectance: self.reflectance,
+            normal_map_texture: texture(&self.normal_map_texture),
+            flip_normal_map_y: self.flip_normal_map_y,
+            occlusion_texture: texture(&self.occlusion_texture),
+            double_sided: self.double_sided,
+            cull_mode: self.cull_mode.map(|face| match face {
+                FaceData::Front => Face::Front,
+                FaceData::Back => Face::Back,
+            }),
+            unlit: self.unlit,
+            fog_enabled: self.fog_enabled,
+            alpha_mode: match self.alpha_mode {
+                AlphaModeData::Opaque => AlphaMode::Opaque,
+                AlphaModeData::Mask(cutoff) => AlphaMode::Mask(cutoff),
+                AlphaModeData::Blend => AlphaMode::Blend,
+                AlphaModeData::Premultiplied => AlphaMode::Premultiplied,
+                AlphaModeData::Add => AlphaMode::Add,
+                AlphaModeData::Multiply => AlphaMode::Multiply,
+            },
+            depth_bias: self.depth_bias,
+            depth_map: texture(&self.depth_map),
+            parallax_depth_scale: self.parallax_depth_scale,
+            parallax_mapping_method: match self.parallax_mapping_method {
+                ParallaxMappingData::Occlusion => ParallaxMappingMethod::Occlusion,
+                ParallaxMappingData::Relief { max_steps } => {
+                    ParallaxMappingMethod::Relief { max_steps }
+                }
+            },
+            max_parallax_layer_count: self.max_parallax_layer_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::HandleId;
+    use std::collections::HashMap;
+
+    #[test]
+    fn material_data_round_trips_through_standard_material() {
+        let texture = |name: &str| Some(format!("textures/{}.png", name));
+        let material_data = MaterialData {
+            base_color: Color::rgba(0.1, 0.2, 0.3, 0.4),
+            base_color_texture: texture("base_color"),
+            emissive: Color::rgb(0.5, 0.6, 0.7),
+            emissive_texture: texture("emissive"),
+            perceptual_roughness: 0.25,
+            metallic: 0.75,
+            metallic_roughness_texture: texture("metallic_roughness"),
+            reflectance: 0.1,
+            normal_map_texture: Some("model.gltf#Texture2".to_string()),
+            flip_normal_map_y: true,
+            occlusion_texture: texture("occlusion"),
+            double_sided: true,
+            cull_mode: Some(FaceData::Front),
+            unlit: true,
+            fog_enabled: false,
+            alpha_mode: AlphaModeData::Mask(0.3),
+            depth_bias: 2.0,
+            depth_map: texture("depth"),
+            parallax_depth_scale: 0.2,
+            parallax_mapping_method: ParallaxMappingData::Relief { max_steps: 7 },
+            max_parallax_layer_count: 24.0,
+        };
+
+        let mut paths = HashMap::new();
+        let material = material_data.to_material(|path| {
+            let handle_id = HandleId::random::<Image>();
+            paths.insert(handle_id, path.to_string());
+            Handle::weak(handle_id)
+        });
+        assert_eq!(paths.len(), material_data.texture_paths().len());
+        let round_tripped =
+            MaterialData::from_material(&material, |texture| paths.get(&texture.id()).cloned());
+        assert_eq!(round_tripped, material_data);
+    }
+}
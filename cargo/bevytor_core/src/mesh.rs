@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use serde::{Deserialize, Serialize};
+
+/// Raw geometry of a triangle mesh, used to store procedural meshes that are not one of the
+/// editor's simple shapes (ex.: `shape::Box` spawned by a captured app).
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Option<Vec<u32>>,
+}
+
+impl MeshData {
+    /// Returns `None` for meshes that are not triangle lists or have no positions.
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+        let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => normals.clone(),
+            _ => vec![],
+        };
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) => uvs.clone(),
+            _ => vec![],
+        };
+        let indices = mesh.indices().map(|indices| match indices {
+            Indices::U16(indices) => indices.iter().map(|i| *i as u32).collect(),
+            Indices::U32(indices) => indices.clone(),
+        });
+
+        Some(Self {
+            positions: positions.clone(),
+            normals,
+            uvs,
+            indices,
+        })
+    }
+
+    pub fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone());
+        if !self.normals.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals.clone());
+        }
+        if !self.uvs.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs.clone());
+        }
+        mesh.set_indices(self.indices.clone().map(Indices::U32));
+        mesh
+    }
+}
@@ -0,0 +1,122 @@
+//! Project file (`project.bv`) and asset source formats, shared by the editor and
+//! `CapturePlugin`.
+
+use bevy::asset::{AssetPath, HandleId};
+use bevy::utils::Uuid;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Default, Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
+pub struct ProjectDescription {
+    pub name: String,
+    pub path: OsString,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProjectState {
+    pub scene_file: String,
+    pub asset_file: String,
+    pub assets_folder: String,
+    pub script_enabled: bool,
+    // script crates, as folders in the project - older projects only have `scripts`, see
+    // `Project::script_modules`
+    #[serde(default)]
+    pub script_modules: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub project_description: ProjectDescription,
+    pub project_state: ProjectState,
+}
+
+impl Default for ProjectState {
+    fn default() -> Self {
+        Self {
+            scene_file: "initial.scn.ron".to_string(),
+            asset_file: "initial.asset.ron".to_string(),
+            assets_folder: "assets".to_string(),
+            script_enabled: false,
+            script_modules: vec![],
+        }
+    }
+}
+
+impl Project {
+    pub fn script_modules(&self) -> Vec<String> {
+        if self.project_state.script_modules.is_empty() && self.project_state.script_enabled {
+            return vec!["scripts".to_string()];
+        }
+        self.project_state.script_modules.clone()
+    }
+
+    pub fn script_path(&self, module: &str) -> PathBuf {
+        Path::new(self.project_description.path.as_os_str()).join(module)
+    }
+
+    /// Removes the script crate from the project, its folder is kept.
+    pub fn remove_script_module(&mut self, module: &str) {
+        let mut modules = self.script_modules();
+        modules.retain(|existing| existing != module);
+        self.project_state.script_enabled = !modules.is_empty();
+        self.project_state.script_modules = modules;
+    }
+
+    pub fn assets_path(&self) -> PathBuf {
+        Path::new(self.project_description.path.as_os_str())
+            .join("scenes")
+            .join(self.project_state.assets_folder.clone())
+    }
+
+    /// Resolves a file asset reference (relative to the assets folder, with optional `#label`).
+    pub fn asset_path(&self, filepath: &str) -> AssetPath<'static> {
+        let (path, label) = match filepath.split_once('#') {
+            Some((path, label)) => (path, Some(label.to_string())),
+            None => (filepath, None),
+        };
+        AssetPath::new(self.assets_path().join(path), label)
+    }
+
+    /// Inverse of `asset_path`, `None` if the path is not in the assets folder.
+    pub fn relative_asset_path(&self, asset_path: &AssetPath) -> Option<String> {
+        let relative = asset_path.path().strip_prefix(self.assets_path()).ok()?;
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Some(match asset_path.label() {
+            Some(label) => format!("{}#{}", relative, label),
+            None => relative,
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Clone)]
+pub enum AssetSourceType {
+    AsString(String),
+    AsFile(String),
+}
+
+#[derive(Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Clone)]
+pub struct AssetSource {
+    pub source_type: AssetSourceType,
+    pub type_uuid: String,
+    pub uid: u64,
+    // pinned sources are kept even if no entity uses them
+    #[serde(default)]
+    pub pinned: bool,
+    // named sources form a library (ex.: materials that can be assigned in the inspector)
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl AssetSource {
+    pub fn handle_id(&self) -> HandleId {
+        // invalid type uuid fails to load, see `create_asset` in the editor
+        let type_uuid = Uuid::from_str(&self.type_uuid).unwrap_or_default();
+        HandleId::Id(type_uuid, self.uid)
+    }
+}
@@ -0,0 +1,24 @@
+use regex::Regex;
+
+// TODO weird hack for transforming Rect type for area in OrthographicProjection
+pub fn fix_serialized_rect(mut scene_serialized: String) -> String {
+    loop {
+        let re = Regex::new(r"area: \(\n +min: \(\n +x: ([+-]?\d+\.?\d*),\n +y: ([+-]?\d+\.?\d*),\n +\),\n +max: \(\n +x: ([+-]?\d+\.?\d*),\n +y: ([+-]?\d+\.?\d*),\n +\),\n +\),").unwrap();
+        if let Some(caps) = re.captures(&scene_serialized) {
+            let replacement = r"area: (
+            min: (X1, Y1),
+            max: (X2, Y2),
+          ),";
+            let new_val = replacement
+                .replace("X1", caps.get(1).unwrap().into())
+                .replace("Y1", caps.get(2).unwrap().into())
+                .replace("X2", caps.get(3).unwrap().into())
+                .replace("Y2", caps.get(4).unwrap().into());
+
+            scene_serialized = re.replace(&scene_serialized, new_val).to_string();
+        } else {
+            break;
+        }
+    }
+    scene_serialized
+}
@@ -20,5 +20,10 @@ fn derive_dynamic_script(input: TokenStream) -> TokenStream {
             let boxed = Box::new(object);
             Box::into_raw(boxed)
         }
+
+        #[no_mangle]
+        pub extern "C" fn _script_metadata() -> *const std::os::raw::c_char {
+            bevytor_script::script_metadata()
+        }
     })
 }
@@ -3,9 +3,8 @@ extern crate bevytor_script;
 
 use bevy::prelude::*;
 use bevytor_derive::DynamicScript;
-use bevytor_script::{ComponentRegistry, Script};
+use bevytor_script::{register_component, Definition, Script, ScriptContext};
 use serde::{Deserialize, Serialize};
-use std::any::TypeId;
 
 #[derive(Debug, Default, DynamicScript)]
 pub struct TestScript;
@@ -19,20 +18,16 @@ impl Script for TestScript {
         "Le Demo finally!"
     }
 
-    fn run(&self, world: &mut World) -> Option<TypeId> {
-        let mut state =
-            world.query_filtered::<(Entity, &mut Transform, &Velocity), Without<Camera>>();
-        for (entity, mut transform, velocity) in state.iter_mut(world) {
-            transform.translation.x += velocity.0;
+    fn start(&self, _world: &mut World) {}
+
+    fn run(&self, world: &mut World, context: &ScriptContext) {
+        let mut state = world.query_filtered::<(&mut Transform, &Velocity), Without<Camera>>();
+        for (mut transform, velocity) in state.iter_mut(world) {
+            transform.translation.x += velocity.0 * context.delta_seconds();
         }
-        Some(TypeId::of::<Velocity>())
     }
 
-    fn init(&self, world: &mut World) {
-        let registry = world.resource_mut::<AppTypeRegistry>();
-        registry.write().register::<Velocity>();
-        let mut registry2 = world.resource_mut::<ComponentRegistry>();
-        registry2.register::<Velocity>();
-        println!("registered {}", registry2.reg.len());
+    fn init(&self, world: &mut World) -> Vec<Definition> {
+        vec![register_component::<Velocity>(world)]
     }
 }
@@ -1,3 +1,4 @@
+use crate::core::AssetRef;
 use bevy::prelude::{Entity, Event};
 
 #[derive(Event)]
@@ -5,3 +6,18 @@ pub struct SelectEntity(pub Entity);
 
 #[derive(Event)]
 pub struct StartPlaying;
+
+#[derive(Event)]
+pub struct PausePlaying;
+
+#[derive(Event)]
+pub struct StopPlaying;
+
+#[derive(Event)]
+pub struct CopyEntity(pub Entity);
+
+#[derive(Event)]
+pub struct PasteEntities;
+
+#[derive(Event)]
+pub struct AssignAsset(pub Entity, pub AssetRef);
@@ -1,12 +1,22 @@
+use bevy::asset::HandleId;
 use bevy::pbr::wireframe::Wireframe;
 use bevy::prelude::*;
+use bevy::utils::Uuid;
 use bevy::window::PrimaryWindow;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use unknown_components::UnknownComponents;
 
 pub mod events;
 pub mod popup;
+pub mod procedural_mesh;
+pub mod unknown_components;
 
 pub fn to_dynamic_scene(world: &World) -> DynamicScene {
+    to_dynamic_scene_of(world, world.iter_entities().map(|r| r.id()))
+}
+
+pub fn to_dynamic_scene_of(world: &World, entities: impl Iterator<Item = Entity>) -> DynamicScene {
     let mut builder = DynamicSceneBuilder::from_world(world);
     builder.deny_all_resources().extract_resources();
     builder
@@ -16,7 +26,8 @@ pub fn to_dynamic_scene(world: &World) -> DynamicScene {
         .deny::<Window>()
         .deny::<PrimaryWindow>()
         .deny::<OriginalEntityId>()
-        .extract_entities(world.iter_entities().map(|r| r.id()))
+        .deny::<UnknownComponents>()
+        .extract_entities(entities)
         .remove_empty_entities();
 
     let scene = builder.build();
@@ -26,3 +37,23 @@ pub fn to_dynamic_scene(world: &World) -> DynamicScene {
 #[derive(Component, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct OriginalEntityId(pub u32);
+
+/// Asset shown in the asset browser - either a registered asset source or a file in the assets
+/// folder, that has no source yet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetRef {
+    Source(HandleId),
+    File(String),
+}
+
+/// Asset that is currently dragged from the asset browser, can be dropped on a hierarchy entity.
+#[derive(Resource, Default)]
+pub struct DraggedAsset(pub Option<AssetRef>);
+
+/// Assets that can be picked for a `Handle<T>` in the inspector, by asset type uuid.
+/// Files are turned into asset sources with `resolve_file` once picked.
+#[derive(Resource)]
+pub struct AssetChoices {
+    pub options: HashMap<Uuid, Vec<(String, AssetRef)>>,
+    pub resolve_file: fn(&mut World, &str) -> Option<HandleId>,
+}
@@ -0,0 +1,306 @@
+use bevy::prelude::*;
+use bevy::render::mesh::shape;
+use bevy_egui::egui::{DragValue, Grid, Ui};
+use serde::{Deserialize, Serialize};
+
+/// Primitive mesh with its parameters, stored as RON in the mesh asset source.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProceduralMesh {
+    Cube {
+        size: f32,
+    },
+    Box {
+        x_length: f32,
+        y_length: f32,
+        z_length: f32,
+    },
+    Plane {
+        size: f32,
+        subdivisions: u32,
+    },
+    Sphere {
+        radius: f32,
+        sectors: usize,
+        stacks: usize,
+    },
+    Capsule {
+        radius: f32,
+        depth: f32,
+        rings: usize,
+        latitudes: usize,
+        longitudes: usize,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+        resolution: u32,
+        segments: u32,
+    },
+    Torus {
+        radius: f32,
+        ring_radius: f32,
+        subdivisions_segments: usize,
+        subdivisions_sides: usize,
+    },
+    Icosphere {
+        radius: f32,
+        subdivisions: usize,
+    },
+}
+
+impl ProceduralMesh {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProceduralMesh::Cube { .. } => "Cube",
+            ProceduralMesh::Box { .. } => "Box",
+            ProceduralMesh::Plane { .. } => "Plane",
+            ProceduralMesh::Sphere { .. } => "Sphere",
+            ProceduralMesh::Capsule { .. } => "Capsule",
+            ProceduralMesh::Cylinder { .. } => "Cylinder",
+            ProceduralMesh::Torus { .. } => "Torus",
+            ProceduralMesh::Icosphere { .. } => "Icosphere",
+        }
+    }
+
+    pub fn to_mesh(&self) -> Mesh {
+        match *self {
+            ProceduralMesh::Cube { size } => Mesh::from(shape::Cube { size }),
+            ProceduralMesh::Box {
+                x_length,
+                y_length,
+                z_length,
+            } => Mesh::from(shape::Box::new(x_length, y_length, z_length)),
+            ProceduralMesh::Plane { size, subdivisions } => {
+                Mesh::from(shape::Plane { size, subdivisions })
+            }
+            ProceduralMesh::Sphere {
+                radius,
+                sectors,
+                stacks,
+            } => Mesh::from(shape::UVSphere {
+                radius,
+                sectors,
+                stacks,
+            }),
+            ProceduralMesh::Capsule {
+                radius,
+                depth,
+                rings,
+                latitudes,
+                longitudes,
+            } => Mesh::from(shape::Capsule {
+                radius,
+                depth,
+                rings,
+                latitudes,
+                longitudes,
+                ..default()
+            }),
+            ProceduralMesh::Cylinder {
+                radius,
+                height,
+                resolution,
+                segments,
+            } => Mesh::from(shape::Cylinder {
+                radius,
+                height,
+                resolution,
+                segments,
+            }),
+            ProceduralMesh::Torus {
+                radius,
+                ring_radius,
+                subdivisions_segments,
+                subdivisions_sides,
+            } => Mesh::from(shape::Torus {
+                radius,
+                ring_radius,
+                subdivisions_segments,
+                subdivisions_sides,
+            }),
+            ProceduralMesh::Icosphere {
+                radius,
+                subdivisions,
+            } => Mesh::try_from(shape::Icosphere {
+                radius,
+                // more subdivisions are not supported, see `FromIcosphereError`
+                subdivisions: subdivisions.min(79),
+            })
+            .unwrap(),
+        }
+    }
+
+    /// Shows parameters as editable values, returns true if any of them changed.
+    pub fn ui(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        Grid::new("procedural_mesh").num_columns(2).show(ui, |ui| {
+            let mut float = |ui: &mut Ui, label: &str, value: &mut f32| {
+                ui.label(label);
+                changed |= ui
+                    .add(
+                        DragValue::new(value)
+                            .speed(0.05)
+                            .clamp_range(0.001..=f32::MAX),
+                    )
+                    .changed();
+                ui.end_row();
+            };
+            match self {
+                ProceduralMesh::Cube { size } => float(ui, "Size", size),
+                ProceduralMesh::Box {
+                    x_length,
+                    y_length,
+                    z_length,
+                } => {
+                    float(ui, "X length", x_length);
+                    float(ui, "Y length", y_length);
+                    float(ui, "Z length", z_length);
+                }
+                ProceduralMesh::Plane { size, .. } => float(ui, "Size", size),
+                ProceduralMesh::Sphere { radius, .. } => float(ui, "Radius", radius),
+                ProceduralMesh::Capsule { radius, depth, .. } => {
+                    float(ui, "Radius", radius);
+                    float(ui, "Depth", depth);
+                }
+                ProceduralMesh::Cylinder { radius, height, .. } => {
+                    float(ui, "Radius", radius);
+                    float(ui, "Height", height);
+                }
+                ProceduralMesh::Torus {
+                    radius,
+                    ring_radius,
+                    ..
+                } => {
+                    float(ui, "Radius", radius);
+                    float(ui, "Ring radius", ring_radius);
+                }
+                ProceduralMesh::Icosphere { radius, .. } => float(ui, "Radius", radius),
+            }
+
+            let mut count =
+                |ui: &mut Ui, label: &str, value: &mut usize, min: usize, max: usize| {
+                    ui.label(label);
+                    changed |= ui
+                        .add(DragValue::new(value).clamp_range(min..=max))
+                        .changed();
+                    ui.end_row();
+                };
+            match self {
+                ProceduralMesh::Plane { subdivisions, .. } => {
+                    let mut value = *subdivisions as usize;
+                    count(ui, "Subdivisions", &mut value, 0, 256);
+                    *subdivisions = value as u32;
+                }
+                ProceduralMesh::Sphere {
+                    sectors, stacks, ..
+                } => {
+                    count(ui, "Sectors", sectors, 3, 256);
+                    count(ui, "Stacks", stacks, 2, 256);
+                }
+                ProceduralMesh::Capsule {
+                    rings,
+                    latitudes,
+                    longitudes,
+                    ..
+                } => {
+                    count(ui, "Rings", rings, 0, 256);
+                    count(ui, "Latitudes", latitudes, 2, 256);
+                    count(ui, "Longitudes", longitudes, 3, 256);
+                }
+                ProceduralMesh::Cylinder {
+                    resolution,
+                    segments,
+                    ..
+                } => {
+                    let mut value = *resolution as usize;
+                    count(ui, "Resolution", &mut value, 3, 256);
+                    *resolution = value as u32;
+                    let mut value = *segments as usize;
+                    count(ui, "Segments", &mut value, 1, 256);
+                    *segments = value as u32;
+                }
+                ProceduralMesh::Torus {
+                    subdivisions_segments,
+                    subdivisions_sides,
+                    ..
+                } => {
+                    count(ui, "Segments", subdivisions_segments, 3, 256);
+                    count(ui, "Sides", subdivisions_sides, 3, 256);
+                }
+                ProceduralMesh::Icosphere { subdivisions, .. } => {
+                    count(ui, "Subdivisions", subdivisions, 0, 79);
+                }
+                ProceduralMesh::Cube { .. } | ProceduralMesh::Box { .. } => {}
+            }
+        });
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::reflect::TypeUuid;
+    use bevytor_core::project::{AssetSource, AssetSourceType};
+
+    fn geometry(mesh: &Mesh) -> (Vec<[f32; 3]>, Vec<usize>) {
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+            .unwrap()
+            .to_vec();
+        let indices = mesh.indices().unwrap().iter().collect();
+        (positions, indices)
+    }
+
+    #[test]
+    fn procedural_mesh_round_trips_through_asset_sources() {
+        let meshes = vec![
+            ProceduralMesh::Box {
+                x_length: 1.5,
+                y_length: 2.0,
+                z_length: 0.5,
+            },
+            ProceduralMesh::Sphere {
+                radius: 2.0,
+                sectors: 12,
+                stacks: 8,
+            },
+            ProceduralMesh::Torus {
+                radius: 1.5,
+                ring_radius: 0.1,
+                subdivisions_segments: 16,
+                subdivisions_sides: 6,
+            },
+            ProceduralMesh::Icosphere {
+                radius: 1.0,
+                subdivisions: 2,
+            },
+        ];
+        let sources = meshes
+            .iter()
+            .enumerate()
+            .map(|(uid, mesh)| AssetSource {
+                source_type: AssetSourceType::AsString(ron::to_string(mesh).unwrap()),
+                type_uuid: Mesh::TYPE_UUID.to_string(),
+                uid: uid as u64,
+                pinned: false,
+                name: None,
+            })
+            .collect::<Vec<_>>();
+        // same format as the project's asset file
+        let saved =
+            ron::ser::to_string_pretty(&sources, ron::ser::PrettyConfig::default()).unwrap();
+
+        let loaded: Vec<AssetSource> = ron::from_str(&saved).unwrap();
+        assert_eq!(loaded, sources);
+        for (source, mesh) in loaded.iter().zip(&meshes) {
+            let AssetSourceType::AsString(raw) = &source.source_type else {
+                panic!("mesh source is not a string");
+            };
+            let loaded_mesh: ProceduralMesh = ron::from_str(raw).unwrap();
+            assert_eq!(&loaded_mesh, mesh);
+            assert_eq!(geometry(&loaded_mesh.to_mesh()), geometry(&mesh.to_mesh()));
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use bevy::prelude::*;
+use bevy::reflect::serde::TypedReflectDeserializer;
+use bevy::reflect::{TypeRegistration, TypeRegistry};
+use serde::de::DeserializeSeed;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Components found in a scene file whose types are not in the `AppTypeRegistry` (ex.: script
+/// was not built). Kept as (type name, raw RON) pairs so they can be written back on save
+/// unchanged, or turned into real components once the type gets registered.
+///
+/// Raw values are not interpreted: entity references inside them are not remapped when the scene
+/// is loaded or pasted, so they keep the ids from the file and can point at the wrong entities.
+#[derive(Component, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct UnknownComponents(pub Vec<(String, String)>);
+
+/// Positions of interest in a serialized scene, as written by `DynamicScene::serialize_ron`.
+#[derive(Default)]
+struct SceneLayout {
+    // position of the closing bracket of the `entities` map
+    entities_close: usize,
+    // entity key -> position right after the opening bracket of its `components` map
+    components_open: HashMap<u64, usize>,
+    // entity key, type name, range of the whole entry (incl. trailing comma), range of the value
+    entries: Vec<(u64, String, Range<usize>, Range<usize>)>,
+}
+
+/// Removes all component entries from the scene, that are not registered in the type registry.
+/// Returns the remaining scene source and removed entries, grouped by entity key in the scene.
+/// If the scene cannot be scanned, it is returned unchanged - deserializer will report the error.
+pub fn split_unknown_components(
+    source: &str,
+    type_registry: &TypeRegistry,
+) -> (String, HashMap<u64, Vec<(String, String)>>) {
+    let mut unknown: HashMap<u64, Vec<(String, String)>> = HashMap::new();
+    let Some(layout) = scan_scene(source) else {
+        return (source.to_string(), unknown);
+    };
+
+    let mut stripped = source.to_string();
+    for (entity, type_name, entry, value) in layout.entries.iter().rev() {
+        if type_registry.get_with_name(type_name).is_none() {
+            stripped.replace_range(entry.clone(), "");
+            unknown
+                .entry(*entity)
+                .or_default()
+                .insert(0, (type_name.clone(), source[value.clone()].to_string()));
+        }
+    }
+
+    (stripped, unknown)
+}
+
+/// Writes raw entries back to the serialized scene, at the same place they were read from.
+/// Entities that were removed from the scene as empty are appended to the `entities` map.
+pub fn inject_unknown_components(
+    serialized: &str,
+    unknown: &[(u64, &UnknownComponents)],
+) -> String {
+    let Some(layout) = scan_scene(serialized) else {
+        error!("Cannot scan serialized scene, unknown components will not be saved");
+        return serialized.to_string();
+    };
+
+    let mut insertions: Vec<(usize, String)> = vec![];
+    let mut new_entities = String::new();
+    for (entity, components) in unknown {
+        let entries = components
+            .0
+            .iter()
+            .map(|(type_name, raw)| format!("\n        \"{}\": {},", type_name, raw))
+            .collect::<String>();
+
+        if let Some(position) = layout.components_open.get(entity) {
+            insertions.push((*position, entries));
+        } else {
+            new_entities.push_str(&format!(
+                "    {}: (\n      components: {{{}\n      }},\n    ),\n",
+                entity, entries
+            ));
+        }
+    }
+
+    if !new_entities.is_empty() {
+        let close = layout.entities_close;
+        let line_start = serialized[..close].rfind('\n').map_or(0, |i| i + 1);
+        if serialized[line_start..close].trim().is_empty() {
+            insertions.push((line_start, new_entities));
+        } else {
+            // empty map, written as `{}`
+            insertions.push((close, format!("\n{}  ", new_entities)));
+        }
+    }
+
+    let mut result = serialized.to_string();
+    insertions.sort_by_key(|(position, _)| *position);
+    for (position, text) in insertions.into_iter().rev() {
+        result.insert_str(position, &text);
+    }
+    result
+}
+
+/// Turns raw entries into components for all types that are now registered (ex.: script loaded).
+pub fn resolve_unknown_components(world: &mut World) {
+    let type_registry_arc = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry_arc.read();
+
+    let entities = world
+        .iter_entities()
+        .filter(|entity| entity.contains::<UnknownComponents>())
+        .map(|entity| entity.id())
+        .collect::<Vec<_>>();
+
+    for entity in entities {
+        let mut entity_mut = world.entity_mut(entity);
+        let Some(mut unknown) = entity_mut.take::<UnknownComponents>() else {
+            continue;
+        };
+
+        unknown.0.retain(|(type_name, raw)| {
+            let Some(registration) = type_registry.get_with_name(type_name) else {
+                return true;
+            };
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                return true;
+            };
+            match deserialize_raw(raw, registration, &type_registry) {
+                Ok(value) => {
+                    reflect_component.insert(&mut entity_mut, &*value);
+                    false
+                }
+                Err(e) => {
+                    error!("Cannot deserialize unknown component {}: {}", type_name, e);
+                    true
+                }
+            }
+        });
+
+        if !unknown.0.is_empty() {
+            entity_mut.insert(unknown);
+        }
+    }
+}
+
+fn deserialize_raw(
+    raw: &str,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, ron::Error> {
+    let mut deserializer = ron::de::Deserializer::from_str(raw)?;
+    TypedReflectDeserializer::new(registration, type_registry).deserialize(&mut deserializer)
+}
+
+fn scan_scene(source: &str) -> Option<SceneLayout> {
+    let mut layout = SceneLayout::default();
+    let mut cursor = Cursor::new(source);
+
+    cursor.expect(b'(')?;
+    while !cursor.eat(b')') {
+        let field = cursor.ident()?;
+        cursor.expect(b':')?;
+        if field == "entities" {
+            scan_entities(&mut cursor, &mut layout)?;
+        } else {
+            cursor.value()?;
+        }
+        cursor.eat(b',');
+    }
+
+    Some(layout)
+}
+
+fn scan_entities(cursor: &mut Cursor, layout: &mut SceneLayout) -> Option<()> {
+    cursor.expect(b'{')?;
+    loop {
+        cursor.skip_whitespace();
+        if cursor.peek() == Some(b'}') {
+            layout.entities_close = cursor.pos;
+            cursor.pos += 1;
+            return Some(());
+        }
+        let entity = cursor.ident()?.parse::<u64>().ok()?;
+        cursor.expect(b':')?;
+        cursor.expect(b'(')?;
+        while !cursor.eat(b')') {
+            let field = cursor.ident()?;
+            cursor.expect(b':')?;
+            if field == "components" {
+                scan_components(cursor, layout, entity)?;
+            } else {
+                cursor.value()?;
+            }
+            cursor.eat(b',');
+        }
+        cursor.eat(b',');
+    }
+}
+
+fn scan_components(cursor: &mut Cursor, layout: &mut SceneLayout, entity: u64) -> Option<()> {
+    cursor.expect(b'{')?;
+    layout.components_open.insert(entity, cursor.pos);
+    loop {
+        cursor.skip_whitespace();
+        if cursor.eat(b'}') {
+            return Some(());
+        }
+        let entry_start = cursor.pos;
+        let type_name = cursor.string()?;
+        cursor.expect(b':')?;
+        cursor.skip_whitespace();
+        let value = cursor.value()?;
+        cursor.eat(b',');
+        layout
+            .entries
+            .push((entity, type_name, entry_start..cursor.pos, value));
+    }
+}
+
+/// Minimal RON scanner - only tracks positions, values are skipped with bracket matching.
+struct Cursor<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if self.source[self.pos..].starts_with("//") {
+                self.pos = self.source[self.pos..]
+                    .find('\n')
+                    .map_or(self.source.len(), |i| self.pos + i);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Option<()> {
+        self.eat(c).then_some(())
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == b'_' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        (self.pos > start).then(|| &self.source[start..self.pos])
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'"') {
+            return None;
+        }
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(self.source[start..self.pos - 1].to_string());
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+
+    fn quoted(&mut self, quote: u8) -> Option<()> {
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += if c == b'\\' { 2 } else { 1 };
+            if c == quote {
+                return Some(());
+            }
+        }
+        None
+    }
+
+    /// Skips a single value, stops at the separator or closing bracket of the enclosing element.
+    fn value(&mut self) -> Option<Range<usize>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                b'"' | b'\'' => {
+                    self.quoted(c)?;
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' if depth == 0 => break,
+                b')' | b']' | b'}' => depth -= 1,
+                b',' if depth == 0 => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let end = start + self.source[start..self.pos].trim_end().len();
+        (end > start).then_some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Known {
+        value: i32,
+    }
+
+    #[derive(Component, Reflect, Default, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Later {
+        value: i32,
+        label: String,
+    }
+
+    const UNKNOWN_NAME: &str = "scripts::Unknown";
+    // nested maps and strings with brackets and quotes must not confuse the scanner
+    const UNKNOWN_RAW: &str = r#"(map: {"a": (b: [1, 2]), "}{": ")(\"]"}, text: "{not a map}")"#;
+
+    fn registry() -> TypeRegistry {
+        let mut type_registry = TypeRegistry::new();
+        type_registry.register::<Known>();
+        type_registry
+    }
+
+    fn scene() -> String {
+        let known_name = std::any::type_name::<Known>();
+        format!(
+            r#"(
+  resources: {{}},
+  entities: {{
+    0: (
+      components: {{
+        "{known}": (value: 1),
+        "{unknown}": {raw},
+      }},
+    ),
+    1: (
+      components: {{
+        "{known}": (value: 2),
+      }},
+    ),
+  }},
+)"#,
+            known = known_name,
+            unknown = UNKNOWN_NAME,
+            raw = UNKNOWN_RAW
+        )
+    }
+
+    fn without_whitespace(text: &str) -> String {
+        text.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+
+    #[test]
+    fn split_keeps_raw_values_and_known_components() {
+        let (stripped, unknown) = split_unknown_components(&scene(), &registry());
+
+        assert_eq!(
+            unknown.get(&0),
+            Some(&vec![(UNKNOWN_NAME.to_string(), UNKNOWN_RAW.to_string())])
+        );
+        // entity without unknown components is left out
+        assert!(!unknown.contains_key(&1));
+        assert!(!stripped.contains(UNKNOWN_NAME));
+        assert!(stripped.contains("(value: 1)"));
+        assert!(stripped.contains("(value: 2)"));
+    }
+
+    #[test]
+    fn inject_restores_split_components() {
+        let original = scene();
+        let (stripped, unknown) = split_unknown_components(&original, &registry());
+        let components = UnknownComponents(unknown[&0].clone());
+
+        let injected = inject_unknown_components(&stripped, &[(0, &components)]);
+        let (stripped_again, unknown_again) = split_unknown_components(&injected, &registry());
+
+        assert_eq!(unknown_again, unknown);
+        assert_eq!(
+            without_whitespace(&stripped_again),
+            without_whitespace(&stripped)
+        );
+        assert_eq!(
+            without_whitespace(&injected).len(),
+            without_whitespace(&original).len()
+        );
+    }
+
+    #[test]
+    fn inject_adds_entities_removed_as_empty() {
+        let (stripped, _) = split_unknown_components(&scene(), &registry());
+        let components = UnknownComponents(vec![(UNKNOWN_NAME.to_string(), "()".to_string())]);
+
+        let injected = inject_unknown_components(&stripped, &[(7, &components)]);
+        let (_, unknown) = split_unknown_components(&injected, &registry());
+
+        assert_eq!(
+            unknown.get(&7),
+            Some(&vec![(UNKNOWN_NAME.to_string(), "()".to_string())])
+        );
+    }
+
+    #[test]
+    fn inject_into_empty_entities_map() {
+        let empty = "(\n  resources: {},\n  entities: {},\n)";
+        let components = UnknownComponents(vec![(UNKNOWN_NAME.to_string(), "()".to_string())]);
+
+        let injected = inject_unknown_components(empty, &[(3, &components)]);
+        let (_, unknown) = split_unknown_components(&injected, &registry());
+
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown.contains_key(&3));
+    }
+
+    #[test]
+    fn resolve_inserts_components_once_registered() {
+        let mut world = World::new();
+        world.insert_resource(AppTypeRegistry::default());
+        let later_name = std::any::type_name::<Later>().to_string();
+        let entity = world
+            .spawn(UnknownComponents(vec![
+                (
+                    later_name.clone(),
+                    r#"(value: 5, label: "a}b")"#.to_string(),
+                ),
+                (UNKNOWN_NAME.to_string(), UNKNOWN_RAW.to_string()),
+            ]))
+            .id();
+
+        // script not loaded yet, nothing changes
+        resolve_unknown_components(&mut world);
+        assert!(world.get::<Later>(entity).is_none());
+        assert_eq!(world.get::<UnknownComponents>(entity).unwrap().0.len(), 2);
+
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Later>();
+        resolve_unknown_components(&mut world);
+
+        assert_eq!(
+            world.get::<Later>(entity),
+            Some(&Later {
+                value: 5,
+                label: "a}b".to_string(),
+            })
+        );
+        assert_eq!(
+            world.get::<UnknownComponents>(entity).unwrap().0,
+            vec![(UNKNOWN_NAME.to_string(), UNKNOWN_RAW.to_string())]
+        );
+    }
+}
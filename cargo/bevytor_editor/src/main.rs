@@ -11,6 +11,7 @@ mod modules;
 
 use crate::plugin::EditorPlugin;
 use bevy::asset::HandleId;
+use bevy::log::LogPlugin;
 use bevy::prelude::*;
 use bevy::reflect::{
     FromType, TypeData, TypeInfo, TypeRegistration, TypeRegistry, TypeRegistryArc,
@@ -36,6 +37,18 @@ use std::time::Duration;
 //}
 
 fn main() {
+    // `bevytor_editor import <project path> <files>...` - import assets without opening the editor
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.len() >= 4 && args[1] == "import" {
+        // only for the console output of the import
+        App::new().add_plugins(LogPlugin::default());
+        if let Err(e) = plugin::import_assets_cli(&args[2], &args[3..]) {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut app = App::new();
     app.register_type::<SkipSerialization>()
         .add_plugins(DefaultPlugins)
@@ -1,4 +1,4 @@
-use crate::core::events::StartPlaying;
+use crate::core::events::{PausePlaying, StartPlaying, StopPlaying};
 use crate::core::{to_dynamic_scene, OriginalEntityId};
 use crate::modules::hierarchy::get_label;
 use bevy::diagnostic::{Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
@@ -6,6 +6,7 @@ use bevy::ecs::entity::EntityMap;
 use bevy::ecs::system::Command;
 use bevy::prelude::*;
 use bevy_egui::egui;
+use bevytor_script::PlayState;
 use serde::{Deserialize, Serialize};
 
 #[derive(Event)]
@@ -28,6 +29,16 @@ impl Default for ControlState {
     }
 }
 
+impl ControlState {
+    pub fn play_state(&self) -> PlayState {
+        match (self.playing, self.initial) {
+            (true, _) => PlayState::Playing,
+            (false, false) => PlayState::Paused,
+            (false, true) => PlayState::Editing,
+        }
+    }
+}
+
 pub struct Controls;
 
 impl Plugin for Controls {
@@ -117,11 +128,10 @@ impl Controls {
                         if state.playing && ui.button("⏸").clicked() {
                             // TODO when paused, all modifications of scene should be disabled (read-only access)
                             // TODO since stop will reset the scene to a state before clicking play !
-                            state.playing = false;
+                            world.send_event(PausePlaying);
                         }
                         if !state.initial && ui.button("■").clicked() {
-                            state.playing = false;
-                            world.send_event(ResetWorldEvent);
+                            world.send_event(StopPlaying);
                         }
                     });
                 });
@@ -1,4 +1,5 @@
-use crate::core::events::SelectEntity;
+use crate::core::events::{AssignAsset, CopyEntity, PasteEntities, SelectEntity};
+use crate::core::DraggedAsset;
 use crate::modules::hierarchy::tree::NodeAction;
 use crate::third_party::clone_entity::CloneEntity;
 use bevy::ecs::entity::Entities;
@@ -7,6 +8,7 @@ use bevy::prelude::*;
 use bevy_egui::egui;
 use bevytor_core::SelectedEntity;
 use std::collections::HashMap;
+pub use tree::drag_source;
 pub use tree::Tree;
 pub use tree::TreeAction;
 
@@ -50,8 +52,34 @@ impl Hierarchy {
             TreeAction::Move(dragged, dropped) => {
                 world.send_event(MoveEntity(dragged, dropped.entity()))
             }
+            TreeAction::Drop(entity) => {
+                if let Some(asset) = world.resource_mut::<DraggedAsset>().0.take() {
+                    world.send_event(AssignAsset(entity, asset));
+                }
+            }
             TreeAction::NoAction => {}
         }
+
+        // Copy & paste through system clipboard, only if keyboard is not used by other widget
+        if !ui.ctx().wants_keyboard_input() {
+            let (copy, paste) = ui.input(|input| {
+                (
+                    input.modifiers.command && input.key_pressed(egui::Key::C),
+                    input.modifiers.command && input.key_pressed(egui::Key::V),
+                )
+            });
+            if copy {
+                if let Ok(entity) = world
+                    .query_filtered::<Entity, With<SelectedEntity>>()
+                    .get_single(world)
+                {
+                    world.send_event(CopyEntity(entity));
+                }
+            }
+            if paste {
+                world.send_event(PasteEntities);
+            }
+        }
     }
 }
 
@@ -14,6 +14,8 @@ pub enum TreeAction {
     NoAction,
     Node(NodeAction),
     Move(Entity, HoverEntity),
+    // something that is not a node (ex.: asset) was dropped on the entity
+    Drop(Entity),
 }
 
 #[derive(Debug, Clone)]
@@ -64,6 +66,12 @@ impl Tree {
                     }
                 }
             }
+        } else if let Some(HoverEntity::Node(entity)) = context.hover_entity {
+            if ui.input(|ui| ui.pointer.any_released()) {
+                if let TreeAction::NoAction = &action {
+                    return TreeAction::Drop(entity);
+                }
+            }
         }
 
         action
@@ -1,10 +1,10 @@
 use crate::error::EResult;
-use bevy::asset::{Handle, HandleId};
+use bevy::asset::{Asset, Handle, HandleId};
 use bevy::prelude::*;
 use bevy::reflect::{Array, List, ReflectMut, Tuple};
 use bevy_egui::egui::{Grid, Ui};
 use bevytor_core::SelectedEntity;
-use inspectable::Inspectable;
+use inspectable::{asset_picker, Inspectable};
 use std::any::{Any, TypeId};
 use std::collections::{HashMap, HashSet};
 
@@ -54,6 +54,10 @@ impl Default for InspectRegistry {
         // new.register::<Transform>();
         new.register::<StandardMaterial>();
         new.register::<Name>();
+        new.register_asset_picker::<Image>();
+        new.register_asset_picker::<AudioSource>();
+        new.register_asset_picker::<Font>();
+        new.register_asset_picker::<ColorMaterial>();
         new
     }
 }
@@ -71,6 +75,18 @@ impl InspectRegistry {
         );
     }
 
+    /// Handles of the asset type are shown as a choice of the project assets.
+    pub fn register_asset_picker<T: Asset>(&mut self) {
+        self.impls.insert(
+            TypeId::of::<Handle<T>>(),
+            Box::new(|value: &mut dyn Any, ui: &mut Ui, context: &mut Context| {
+                if let Some(handle) = value.downcast_mut::<Handle<T>>() {
+                    asset_picker(handle, ui, context);
+                }
+            }),
+        );
+    }
+
     pub fn exec_reflect(
         &self,
         value: &mut dyn Reflect,
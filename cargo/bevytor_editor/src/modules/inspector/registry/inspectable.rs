@@ -1,11 +1,14 @@
 use bevy::math::{EulerRot, Quat, Vec2, Vec3};
 use bevy_egui::egui::{Checkbox, Ui};
 use bevy::pbr::StandardMaterial;
-use bevy::asset::{Asset, Assets, Handle};
+use bevy::asset::{Asset, AssetServer, Assets, Handle};
 use bevy::prelude::{Mut, Reflect, Transform};
 use bevy::core::Name;
 use bevy_egui::egui;
 use crate::modules::inspector::registry::Context;
+use crate::core::{AssetChoices, AssetRef};
+use bevy::asset::HandleId;
+use bevy::reflect::TypeUuid;
 
 pub trait Inspectable {
     fn ui(&mut self, ui: &mut Ui, context: &mut Context);
@@ -127,3 +130,45 @@ impl Inspectable for Name {
         });
     }
 }
+
+/// Choice of the project assets of the handle type, see `AssetChoices`.
+pub fn asset_picker<T: Asset>(handle: &mut Handle<T>, ui: &mut Ui, context: &mut Context) {
+    // UNSAFE only resources are modified, when a file is turned into an asset source
+    let world = unsafe { &mut *context.world };
+    let Some(asset_choices) = world.get_resource::<AssetChoices>() else {
+        ui.label(format!("{:?}", handle.id()));
+        return;
+    };
+    let options = asset_choices
+        .options
+        .get(&T::TYPE_UUID)
+        .cloned()
+        .unwrap_or_default();
+    let resolve_file = asset_choices.resolve_file;
+
+    let current = AssetRef::Source(handle.id());
+    let selected_text = options
+        .iter()
+        .find(|(_, asset)| *asset == current)
+        .map_or("-".to_string(), |(label, _)| label.clone());
+    let mut picked = None;
+    egui::ComboBox::from_id_source((T::TYPE_UUID, handle.id()))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            for (label, asset) in options {
+                if ui.selectable_label(asset == current, label).clicked() {
+                    picked = Some(asset);
+                }
+            }
+        });
+
+    let handle_id: Option<HandleId> = match picked {
+        Some(AssetRef::Source(handle_id)) => Some(handle_id),
+        Some(AssetRef::File(file)) => resolve_file(world, &file),
+        None => None,
+    };
+    if let Some(handle_id) = handle_id {
+        // strong handle, so the asset is kept while the component uses it
+        *handle = world.resource::<AssetServer>().get_handle(handle_id);
+    }
+}
@@ -3,49 +3,66 @@ General TODOs:
 - handle unwraps as errors
 */
 
-use crate::error::EResult;
+use crate::bail;
+use crate::error::{EResult, Error};
 use crate::logs::{logs_ui, Level, LogBuffer, LogPlugin, PushLog};
-use crate::scripts::{handle_tasks, ScriptableRegistry};
+use crate::scripts::{handle_tasks, watch_scripts, BuildStatus, ScriptWatcher, ScriptableRegistry};
 use crate::service::existing_projects::ExistingProjects;
-use crate::service::project::{Project, ProjectDescription};
+use crate::service::project::{AssetFileKind, Project, ProjectDescription, ProjectFiles};
+use crate::ui::assets::{asset_browser, AssetGroup, AssetItem};
+use crate::ui::build_problems::build_problems_ui;
 use crate::ui::project::{project_list, ProjectListAction};
 use bevy::app::AppLabel;
-use bevy::asset::{Asset, HandleId};
+use bevy::asset::{Asset, AssetPath, HandleId};
 use bevy::ecs::entity::EntityMap;
 use bevy::ecs::system::{Command, SystemState};
+use bevy::gltf::{Gltf, GltfMesh};
 use bevy::pbr::wireframe::{Wireframe, WireframePlugin};
 use bevy::prelude::*;
-use bevy::reflect::{Array, List, Tuple, TypeUuid};
+use bevy::reflect::{Array, List, ReflectMut, ReflectRef, Tuple, TypeUuid};
+use bevy::scene::serde::SceneDeserializer;
 use bevy::scene::serialize_ron;
 use bevy::utils::Uuid;
 use bevy::window::PrimaryWindow;
 use bevy_egui::egui::Ui;
-use bevy_egui::{egui, EguiContext, EguiPlugin};
+use bevy_egui::{egui, EguiClipboard, EguiContext, EguiPlugin};
 //use bevy_mod_picking::{PickableBundle, PickingCamera, PickingCameraBundle};
 //use bevy_transform_gizmo::{GizmoPickSource, GizmoSettings};
-use crate::core::events::{SelectEntity, StartPlaying};
+use crate::core::events::{
+    AssignAsset, CopyEntity, PasteEntities, PausePlaying, SelectEntity, StartPlaying, StopPlaying,
+};
 use crate::core::popup::{show_popup, BoxedPopup};
-use crate::core::OriginalEntityId;
-use crate::modules::controls::{ControlState, Controls, EditorCamera, ResetWorldEvent};
+use crate::core::procedural_mesh::ProceduralMesh;
+use crate::core::unknown_components::{
+    inject_unknown_components, split_unknown_components, UnknownComponents,
+};
+use crate::core::{AssetChoices, AssetRef, DraggedAsset, OriginalEntityId};
+use crate::modules::controls::{ControlState, Controls, EditorCamera, ResetWorld, ResetWorldEvent};
 use crate::modules::hierarchy::*;
 use crate::modules::inspector::registry::InspectRegistry;
 use crate::modules::inspector::Inspector;
 use bevy::core_pipeline::core_3d::Camera3dDepthTextureUsage;
 use bevy::render::camera;
 use bevy_mod_picking::debug::print;
+use bevytor_core::material::{ColorMaterialData, MaterialData};
+use bevytor_core::mesh::MeshData;
+use bevytor_core::project::{AssetSource, AssetSourceType};
+use bevytor_core::scene::fix_serialized_rect;
 use bevytor_core::SelectedEntity;
-use bevytor_script::ComponentRegistry;
+use bevytor_script::{ComponentRegistry, ScriptContext, ScriptSystems, ScriptUpdate};
 use regex::Regex;
+use serde::de::DeserializeSeed;
 use serde::{Deserialize, Serialize};
 use smooth_bevy_cameras::controllers::orbit::{
     OrbitCameraBundle, OrbitCameraController, OrbitCameraPlugin,
 };
 use smooth_bevy_cameras::LookTransformPlugin;
 use std::any::TypeId;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use sysinfo::{RefreshKind, SystemExt};
 
 pub struct EditorPlugin {
@@ -63,7 +80,6 @@ impl Default for EditorPlugin {
 pub struct EditorState {
     // TODO re/load existing projects only when needed: on start, window opened, new project opened/created
     existing_projects: ExistingProjects,
-    current_file_explorer_path: PathBuf,
     current_project: Option<Project>,
     tree: Tree,
     new_project_popup_shown: bool,
@@ -73,6 +89,13 @@ pub struct EditorState {
     existing_project_popup_shown: bool,
     existing_project_path: String,
 
+    import_asset_popup_shown: bool,
+    import_asset_path: String,
+
+    new_material_name: String,
+
+    new_script_module_name: String,
+
     system_info: sysinfo::System,
 
     current_popup: Option<BoxedPopup>,
@@ -82,7 +105,6 @@ impl Default for EditorState {
     fn default() -> Self {
         Self {
             existing_projects: Default::default(),
-            current_file_explorer_path: Default::default(),
             current_project: None,
             tree: Default::default(),
             new_project_popup_shown: false,
@@ -90,6 +112,10 @@ impl Default for EditorState {
             new_project_path: "".to_string(),
             existing_project_popup_shown: false,
             existing_project_path: "".to_string(),
+            import_asset_popup_shown: false,
+            import_asset_path: "".to_string(),
+            new_material_name: "".to_string(),
+            new_script_module_name: "".to_string(),
             system_info: sysinfo::System::new_with_specifics(RefreshKind::new().with_disks_list()),
             current_popup: None,
         }
@@ -124,7 +150,10 @@ struct PreSaveProject();
 struct SaveProject();
 
 #[derive(Event)]
-struct LoadScene(Handle<DynamicScene>);
+struct CleanUpAssets;
+
+#[derive(Event)]
+struct LoadScene(Handle<DynamicScene>, HashMap<u64, Vec<(String, String)>>);
 
 impl Command for LoadScene {
     fn apply(self, world: &mut World) {
@@ -132,11 +161,22 @@ impl Command for LoadScene {
         world.resource_scope(|world, dynamic_scenes: Mut<Assets<DynamicScene>>| {
             if let Some(scene) = dynamic_scenes.get(&self.0) {
                 println!("Will attach scene to world");
-                scene
-                    .write_to_world(world, &mut EntityMap::default())
-                    .unwrap();
+                let mut entity_map = EntityMap::default();
+                scene.write_to_world(world, &mut entity_map).unwrap();
                 println!("Attached scene to world");
 
+                // Attach components, which types were not registered while loading the scene
+                for (scene_entity, components) in self.1 {
+                    if let Some(entity) = entity_map.get(Entity::from_bits(scene_entity)) {
+                        world
+                            .entity_mut(entity)
+                            .insert(UnknownComponents(components));
+                    } else {
+                        // entity had no known components
+                        world.spawn(UnknownComponents(components));
+                    }
+                }
+
                 /*world.resource_scope(|world, mut editor_state: Mut<EditorState>| {
                     editor_state.dynamic_scene_handle = Some(self.0.clone());
                 });*/
@@ -175,31 +215,35 @@ impl Command for LoadScene {
                 //let mut gizmo_settings = world.resource_mut::<GizmoSettings>();
                 //gizmo_settings.enabled =
 
-                // Attach ComputedVisibility to all entities with Mesh
-                // since Component fails to serialize and is temporary skipped from serialization
-                let mut visibility_query = world.query_filtered::<Entity, With<Handle<Mesh>>>();
-                let mut entities = vec![];
-                for entity in visibility_query.iter(world) {
-                    entities.push(entity);
-                }
+                restore_skipped_components(world);
+            }
+        });
 
-                for entity in entities {
-                    world
-                        .entity_mut(entity)
-                        .insert(ComputedVisibility::default());
-                }
+        let mut load_project_progress = world.resource_mut::<LoadProjectProgress>();
+        if let LoadProjectStep::Scene(_, false) = load_project_progress.0 {
+            load_project_progress.0 = LoadProjectStep::Scene(self.0, true);
+        }
+    }
+}
 
-                let mut wireframe_query = world.query_filtered::<Entity, With<FixedWireframe>>();
-                let mut entities = vec![];
-                for entity in wireframe_query.iter(world) {
-                    entities.push(entity);
-                }
+fn restore_skipped_components(world: &mut World) {
+    let entities: Vec<Entity> = world.iter_entities().map(|entity| entity.id()).collect();
+    restore_skipped_components_of(world, &entities);
+}
 
-                for entity in entities {
-                    world.entity_mut(entity).insert(Wireframe);
-                }
-            }
-        });
+fn restore_skipped_components_of(world: &mut World, entities: &[Entity]) {
+    // Attach ComputedVisibility to all entities with Mesh
+    // since Component fails to serialize and is temporary skipped from serialization
+    for entity in entities {
+        let Some(mut entity) = world.get_entity_mut(*entity) else {
+            continue;
+        };
+        if entity.contains::<Handle<Mesh>>() {
+            entity.insert(ComputedVisibility::default());
+        }
+        if entity.contains::<FixedWireframe>() {
+            entity.insert(Wireframe);
+        }
     }
 }
 
@@ -210,20 +254,7 @@ impl Command for LoadAsset {
     fn apply(self, world: &mut World) {
         info!("new asset requested {:?} - will load", self.0);
 
-        let untyped_handle = world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
-            let uuid = Uuid::parse_str(self.0.type_uuid.as_str()).unwrap();
-            let asset_impl = asset_registry.impls.get(&uuid).unwrap();
-            asset_impl.0(&self.0, world)
-        });
-
-        world.resource_scope(|world, mut asset_management: Mut<AssetManagement>| {
-            info!("new asset pushed to mgmt {:?}", self.0);
-            asset_management.push(AssetEntry {
-                source: self.0.clone(),
-                original: untyped_handle,
-                attached: None,
-            });
-        });
+        create_asset(world, &self.0);
 
         world.resource_scope(
             |world, mut load_project_progress: Mut<LoadProjectProgress>| {
@@ -237,53 +268,277 @@ impl Command for LoadAsset {
     }
 }
 
+fn create_asset(world: &mut World, source: &AssetSource) {
+    let result = world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
+        let Ok(uuid) = Uuid::parse_str(source.type_uuid.as_str()) else {
+            bail!("ASSET::LOAD::INVALID_TYPE_UUID", &source.type_uuid);
+        };
+        let Some(asset_impl) = asset_registry.impls.get(&uuid) else {
+            bail!("ASSET::LOAD::UNKNOWN_TYPE", uuid);
+        };
+        asset_impl.0(source, world)
+    });
+
+    let mut entry = AssetEntry {
+        source: source.clone(),
+        original: HandleUntyped::weak(source.handle_id()),
+        attached: None,
+        failed: false,
+    };
+    match result {
+        Ok(untyped_handle) => entry.original = untyped_handle,
+        Err(e) => fail_asset(world, &mut entry, e.to_string()),
+    }
+
+    world.resource_scope(|_, mut asset_management: Mut<AssetManagement>| {
+        info!("new asset pushed to mgmt {:?}", source);
+        asset_management.push(entry);
+    });
+}
+
+/// Assets that failed to load during the last project load, reported when loading finishes.
+#[derive(Default, Resource)]
+struct AssetLoadSummary {
+    total: usize,
+    failed: Vec<(AssetSource, String)>,
+}
+
+/// Marks the entry as failed and replaces the asset with a visible placeholder (if the type has
+/// one), so entities using it stay in the scene.
+fn fail_asset(world: &mut World, entry: &mut AssetEntry, reason: String) {
+    error!("Failed to load asset {:?}: {}", entry.source, reason);
+    entry.failed = true;
+    entry.attached = placeholder_asset(world, entry.source.handle_id());
+    world.send_event(PushLog(
+        format!(
+            "ASSET::LOAD::FAILED -> {} ({})",
+            source_label(&entry.source),
+            reason
+        ),
+        Level::Error,
+    ));
+    world
+        .resource_mut::<AssetLoadSummary>()
+        .failed
+        .push((entry.source.clone(), reason));
+}
+
+fn placeholder_asset(world: &mut World, handle_id: HandleId) -> Option<HandleUntyped> {
+    let HandleId::Id(type_uuid, _) = handle_id else {
+        return None;
+    };
+    let handle = if type_uuid == Mesh::TYPE_UUID {
+        world
+            .resource_mut::<Assets<Mesh>>()
+            .set(handle_id, Mesh::from(shape::Cube { size: 1.0 }))
+            .clone_untyped()
+    } else if type_uuid == StandardMaterial::TYPE_UUID {
+        world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .set(handle_id, StandardMaterial::from(Color::FUCHSIA))
+            .clone_untyped()
+    } else if type_uuid == ColorMaterial::TYPE_UUID {
+        world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .set(handle_id, ColorMaterial::from(Color::FUCHSIA))
+            .clone_untyped()
+    } else {
+        return None;
+    };
+    Some(handle)
+}
+
 #[derive(Default, Resource)]
 struct AssetSourceList(Vec<AssetSource>);
 
-#[derive(Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Clone)]
-enum AssetSourceType {
-    AsString(String),
-    AsFile(String),
+impl AssetSourceList {
+    /// Registers a new source with a uid allocated by `allocate_uid`, `is_live` tells if a handle
+    /// id is already used by a loaded asset.
+    fn add(
+        &mut self,
+        source_type: AssetSourceType,
+        type_uuid: Uuid,
+        is_live: impl Fn(HandleId) -> bool,
+    ) -> AssetSource {
+        let type_uuid = type_uuid.to_string();
+        let source = AssetSource {
+            uid: allocate_uid(&self.0, &type_uuid, source_seed(&source_type), is_live),
+            source_type,
+            type_uuid,
+            pinned: false,
+            name: None,
+        };
+        self.0.push(source.clone());
+        source
+    }
+}
+
+/// Uid derived from the source content (ex.: file path or procedural parameters), so the same
+/// project history always produces the same uids. On collision with an existing source of the
+/// same type or a loaded asset (`is_live`), next attempts are hashed until a free uid is found.
+fn allocate_uid(
+    sources: &[AssetSource],
+    type_uuid: &str,
+    seed: &str,
+    is_live: impl Fn(HandleId) -> bool,
+) -> u64 {
+    let handle_type = Uuid::from_str(type_uuid).unwrap_or_default();
+    let taken = sources
+        .iter()
+        .filter(|source| source.type_uuid == type_uuid)
+        .map(|source| source.uid)
+        .collect::<HashSet<_>>();
+    let mut attempt: u64 = 0;
+    loop {
+        let uid = fnv1a_hash(format!("{}#{}#{}", type_uuid, seed, attempt).as_bytes());
+        if !taken.contains(&uid) && !is_live(HandleId::Id(handle_type, uid)) {
+            return uid;
+        }
+        attempt += 1;
+    }
+}
+
+fn source_seed(source_type: &AssetSourceType) -> &str {
+    match source_type {
+        AssetSourceType::AsString(raw) => raw,
+        AssetSourceType::AsFile(filepath) => filepath,
+    }
 }
 
-#[derive(Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Clone)]
-struct AssetSource {
-    source_type: AssetSourceType,
-    type_uuid: String,
-    uid: u64,
+// stable across platforms and compiler versions, unlike `DefaultHasher`
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
 }
 
 trait AssetSourceable {
-    fn from_string(raw: String) -> EResult<Self>
+    fn from_string(raw: String, world: &World) -> EResult<Self>
     where
         Self: Sized + Asset;
 
-    fn to_string(&self, prev_raw: String) -> EResult<String>
+    fn to_string(&self, prev_raw: String, world: &World) -> EResult<String>
     where
         Self: Asset;
 }
 
 impl AssetSourceable for Mesh {
-    fn from_string(raw: String) -> EResult<Self> {
-        Ok(raw.parse::<MeshMaterial>().unwrap().to_mesh())
+    fn from_string(raw: String, _: &World) -> EResult<Self> {
+        // older projects store only the name of the primitive, with default parameters
+        if let Ok(mesh_material) = raw.parse::<MeshMaterial>() {
+            return Ok(mesh_material.to_mesh());
+        }
+        if let Ok(procedural_mesh) = ron::from_str::<ProceduralMesh>(raw.as_str()) {
+            return Ok(procedural_mesh.to_mesh());
+        }
+        // procedural meshes (ex.: captured from an existing app) are stored as raw geometry
+        match ron::from_str::<MeshData>(raw.as_str()) {
+            Ok(mesh_data) => Ok(mesh_data.to_mesh()),
+            Err(e) => bail!("ASSET::MESH::CANNOT_PARSE", e.to_string()),
+        }
     }
 
-    fn to_string(&self, prev_raw: String) -> EResult<String> {
+    fn to_string(&self, prev_raw: String, _: &World) -> EResult<String> {
         // Do nothing as all information is in the string itself
         Ok(prev_raw)
     }
 }
 
 impl AssetSourceable for StandardMaterial {
-    fn from_string(raw: String) -> EResult<Self> {
-        let color = ron::from_str::<Color>(raw.as_str()).unwrap();
-        Ok(StandardMaterial::from(color))
+    fn from_string(raw: String, world: &World) -> EResult<Self> {
+        // older projects store only the base color
+        if let Ok(color) = ron::from_str::<Color>(raw.as_str()) {
+            return Ok(StandardMaterial::from(color));
+        }
+        let material_data = match ron::from_str::<MaterialData>(raw.as_str()) {
+            Ok(result) => result,
+            Err(e) => bail!("ASSET::MATERIAL::CANNOT_PARSE", e),
+        };
+        let Some(project) = &world.resource::<EditorState>().current_project else {
+            bail!("ASSET::MATERIAL::NO_PROJECT");
+        };
+        let asset_server = world.resource::<AssetServer>();
+        Ok(material_data.to_material(|path| asset_server.load(project.asset_path(path))))
+    }
+
+    fn to_string(&self, _: String, world: &World) -> EResult<String> {
+        let material_data =
+            MaterialData::from_material(self, |texture| texture_asset_path(texture, world));
+        match ron::to_string(&material_data) {
+            Ok(result) => Ok(result),
+            Err(e) => bail!("ASSET::MATERIAL::CANNOT_SERIALIZE", e),
+        }
+    }
+}
+
+impl AssetSourceable for ColorMaterial {
+    fn from_string(raw: String, world: &World) -> EResult<Self> {
+        let material_data = match ron::from_str::<ColorMaterialData>(raw.as_str()) {
+            Ok(result) => result,
+            Err(e) => bail!("ASSET::COLOR_MATERIAL::CANNOT_PARSE", e),
+        };
+        let Some(project) = &world.resource::<EditorState>().current_project else {
+            bail!("ASSET::COLOR_MATERIAL::NO_PROJECT");
+        };
+        let asset_server = world.resource::<AssetServer>();
+        Ok(ColorMaterial {
+            color: material_data.color,
+            texture: material_data
+                .texture
+                .map(|path| asset_server.load(project.asset_path(&path))),
+        })
+    }
+
+    fn to_string(&self, _: String, world: &World) -> EResult<String> {
+        let material_data = ColorMaterialData {
+            color: self.color,
+            texture: self
+                .texture
+                .as_ref()
+                .and_then(|texture| texture_asset_path(texture, world)),
+        };
+        match ron::to_string(&material_data) {
+            Ok(result) => Ok(result),
+            Err(e) => bail!("ASSET::COLOR_MATERIAL::CANNOT_SERIALIZE", e),
+        }
+    }
+}
+
+// assets that can only be loaded from files in the assets folder, see `asset_file_type`
+macro_rules! impl_file_asset_sourceable {
+    ($($asset_type: ty),*) => {
+        $(
+            impl AssetSourceable for $asset_type {
+                fn from_string(_: String, _: &World) -> EResult<Self> {
+                    bail!("ASSET::FILE_ONLY", stringify!($asset_type))
+                }
+
+                fn to_string(&self, prev_raw: String, _: &World) -> EResult<String> {
+                    Ok(prev_raw)
+                }
+            }
+        )*
     }
+}
 
-    fn to_string(&self, _: String) -> EResult<String> {
-        let serialized = ron::to_string(&self.base_color).unwrap();
-        Ok(serialized)
+impl_file_asset_sourceable!(Image, AudioSource, Font);
+
+/// Path of the texture relative to the assets folder, textures loaded from elsewhere are not saved.
+fn texture_asset_path(texture: &Handle<Image>, world: &World) -> Option<String> {
+    let project = world.resource::<EditorState>().current_project.as_ref();
+    let asset_server = world.resource::<AssetServer>();
+    let path = project
+        .and_then(|project| project.relative_asset_path(&asset_server.get_handle_path(texture)?));
+    if path.is_none() {
+        warn!(
+            "Texture {:?} is not a file in the assets folder, not saved",
+            texture
+        );
     }
+    path
 }
 
 #[derive(Resource)]
@@ -291,9 +546,10 @@ struct AssetRegistry {
     impls: HashMap<
         Uuid,
         (
-            Box<fn(&AssetSource, &mut World) -> HandleUntyped>, // create
-            Box<fn(&mut AssetSource, &World) -> ()>,            // update
-            Box<fn(&mut AssetEntry, &mut World) -> ()>,         // attach
+            Box<fn(&AssetSource, &mut World) -> EResult<HandleUntyped>>, // create
+            Box<fn(&mut AssetSource, &World) -> ()>,                     // update
+            Box<fn(&mut AssetEntry, &mut World) -> ()>,                  // attach
+            Box<fn(HandleId, &World) -> bool>,                           // contains
         ),
     >,
 }
@@ -305,6 +561,10 @@ impl Default for AssetRegistry {
         };
         instance.register::<Mesh>();
         instance.register::<StandardMaterial>();
+        instance.register::<ColorMaterial>();
+        instance.register::<Image>();
+        instance.register::<AudioSource>();
+        instance.register::<Font>();
         instance
     }
 }
@@ -318,31 +578,45 @@ impl AssetRegistry {
                     |source: &AssetSource, world: &mut World| match &source.source_type {
                         AssetSourceType::AsString(raw) => {
                             world.resource_scope(|world, mut assets: Mut<Assets<T>>| {
-                                let asset = T::from_string(raw.clone()).unwrap();
-                                assets.add(asset).clone_untyped()
+                                let asset = T::from_string(raw.clone(), world)?;
+                                Ok(assets.add(asset).clone_untyped())
                             })
                         }
-                        AssetSourceType::AsFile(_) => {
-                            /*let asset_path =
-                                Path::new(project.project_description.path.as_os_str())
-                                    .join("scenes")
-                                    .join(project.project_state.assets_folder.clone())
-                                    .join(filepath.clone());
-
-                            asset_server.load_untyped(asset_path.to_str().unwrap())*/
-                            todo!("WIP")
+                        AssetSourceType::AsFile(filepath) => {
+                            let editor_state = world.resource::<EditorState>();
+                            let Some(project) = editor_state.current_project.as_ref() else {
+                                bail!("ASSET::LOAD::NO_PROJECT");
+                            };
+                            let mut asset_path = project.asset_path(filepath);
+                            if asset_path.label().is_none() {
+                                if let Some(label) = default_gltf_label(&asset_path, T::TYPE_UUID) {
+                                    asset_path = AssetPath::new(
+                                        asset_path.path().to_path_buf(),
+                                        Some(label.to_string()),
+                                    );
+                                }
+                            }
+                            let asset_server = world.resource::<AssetServer>();
+                            Ok(asset_server.load_untyped(asset_path))
                         }
                     },
                 ),
                 Box::new(|source: &mut AssetSource, world: &World| {
                     source.source_type = match &source.source_type {
                         AssetSourceType::AsString(raw) => {
-                            let uuid = Uuid::from_str(&source.type_uuid).unwrap();
-                            let handle = Handle::weak(HandleId::Id(uuid, source.uid));
+                            let handle = Handle::weak(source.handle_id());
                             let assets = world.resource::<Assets<T>>();
-                            let asset = assets.get(&handle).unwrap();
-                            let new_raw = asset.to_string(raw.clone()).unwrap();
-                            AssetSourceType::AsString(new_raw)
+                            let new_raw = match assets.get(&handle) {
+                                Some(asset) => asset.to_string(raw.clone(), world),
+                                None => Err(Error::new("ASSET::SAVE::NO_ASSET")),
+                            };
+                            match new_raw {
+                                Ok(new_raw) => AssetSourceType::AsString(new_raw),
+                                Err(e) => {
+                                    error!("Source {:?} is saved unchanged: {}", source, e);
+                                    AssetSourceType::AsString(raw.clone())
+                                }
+                            }
                         }
                         AssetSourceType::AsFile(filepath) => {
                             // Do nothing as handle does not need to be updated at all
@@ -351,24 +625,74 @@ impl AssetRegistry {
                     }
                 }),
                 Box::new(|entry: &mut AssetEntry, world: &mut World| {
-                    world.resource_scope(|world, mut assets: Mut<Assets<T>>| {
-                        handle_attach_asset(&mut assets, entry);
+                    let attached = world.resource_scope(|_, mut assets: Mut<Assets<T>>| {
+                        handle_attach_asset(&mut assets, entry)
                     });
+                    if !attached {
+                        // ex.: file was loaded, but is not of the expected asset type
+                        fail_asset(world, entry, "ASSET::LOAD::NO_ASSET".to_string());
+                    }
+                }),
+                Box::new(|handle_id: HandleId, world: &World| {
+                    world
+                        .get_resource::<Assets<T>>()
+                        .map_or(false, |assets| assets.contains(&Handle::weak(handle_id)))
                 }),
             ),
         );
     }
 
     pub fn attach_asset(&self, entry: &mut AssetEntry, world: &mut World) {
-        let uuid = Uuid::parse_str(entry.source.type_uuid.as_str()).unwrap();
-        let callback = self.impls.get(&uuid).unwrap();
-        callback.2(entry, world);
+        let callback = Uuid::parse_str(entry.source.type_uuid.as_str())
+            .ok()
+            .and_then(|uuid| self.impls.get(&uuid));
+        match callback {
+            Some(callback) => callback.2(entry, world),
+            None => fail_asset(world, entry, "ASSET::LOAD::UNKNOWN_TYPE".to_string()),
+        }
+    }
+
+    /// Whether a loaded asset already uses the handle id.
+    pub fn contains(&self, handle_id: HandleId, world: &World) -> bool {
+        let HandleId::Id(type_uuid, _) = handle_id else {
+            return false;
+        };
+        match self.impls.get(&type_uuid) {
+            Some(callback) => callback.3(handle_id, world),
+            None => false,
+        }
     }
 
     pub fn update_source(&self, source: &mut AssetSource, world: &World) {
-        let uuid = Uuid::parse_str(source.type_uuid.as_str()).unwrap();
-        let callback = self.impls.get(&uuid).unwrap();
-        callback.1(source, world);
+        // failed sources keep their content (ex.: placeholder is not written over it)
+        if world
+            .resource::<AssetManagement>()
+            .iter()
+            .any(|entry| entry.failed && entry.source.handle_id() == source.handle_id())
+        {
+            return;
+        }
+        let callback = Uuid::parse_str(source.type_uuid.as_str())
+            .ok()
+            .and_then(|uuid| self.impls.get(&uuid));
+        if let Some(callback) = callback {
+            callback.1(source, world);
+        }
+    }
+}
+
+/// A glTF file referenced as a mesh or material, without a label, uses its first one.
+fn default_gltf_label(asset_path: &AssetPath, type_uuid: Uuid) -> Option<&'static str> {
+    let extension = asset_path.path().extension()?.to_str()?;
+    if extension != "gltf" && extension != "glb" {
+        return None;
+    }
+    if type_uuid == Mesh::TYPE_UUID {
+        Some("Mesh0/Primitive0")
+    } else if type_uuid == StandardMaterial::TYPE_UUID {
+        Some("Material0")
+    } else {
+        None
     }
 }
 
@@ -377,6 +701,7 @@ struct AssetEntry {
     source: AssetSource,
     original: HandleUntyped,
     attached: Option<HandleUntyped>,
+    failed: bool,
 }
 
 #[derive(Default, Deref, DerefMut, Resource)]
@@ -405,17 +730,31 @@ impl ToString for SimpleObject {
 #[derive(PartialEq)]
 enum MeshMaterial {
     Cube,
+    Box,
     Plane,
     Sphere,
+    Capsule,
+    Cylinder,
+    Torus,
+    Icosphere,
+}
+
+impl MeshMaterial {
+    const ALL: [MeshMaterial; 8] = [
+        MeshMaterial::Cube,
+        MeshMaterial::Box,
+        MeshMaterial::Plane,
+        MeshMaterial::Sphere,
+        MeshMaterial::Capsule,
+        MeshMaterial::Cylinder,
+        MeshMaterial::Torus,
+        MeshMaterial::Icosphere,
+    ];
 }
 
 impl ToString for MeshMaterial {
     fn to_string(&self) -> String {
-        match self {
-            MeshMaterial::Cube => "Cube".to_string(),
-            MeshMaterial::Plane => "Plane".to_string(),
-            MeshMaterial::Sphere => "Sphere".to_string(),
-        }
+        self.default_params().name().to_string()
     }
 }
 
@@ -423,30 +762,60 @@ impl FromStr for MeshMaterial {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Cube" => Ok(MeshMaterial::Cube),
-            "Plane" => Ok(MeshMaterial::Plane),
-            "Sphere" => Ok(MeshMaterial::Sphere),
-            _ => Err(()),
-        }
+        MeshMaterial::ALL
+            .into_iter()
+            .find(|mesh_material| mesh_material.to_string() == s)
+            .ok_or(())
     }
 }
 
 impl MeshMaterial {
-    fn to_mesh(&self) -> Mesh {
+    fn default_params(&self) -> ProceduralMesh {
         match self {
-            MeshMaterial::Cube => Mesh::from(shape::Cube { size: 1.0 }),
-            MeshMaterial::Plane => Mesh::from(shape::Plane {
+            MeshMaterial::Cube => ProceduralMesh::Cube { size: 1.0 },
+            MeshMaterial::Box => ProceduralMesh::Box {
+                x_length: 1.0,
+                y_length: 1.0,
+                z_length: 1.0,
+            },
+            MeshMaterial::Plane => ProceduralMesh::Plane {
                 size: 1.0,
                 subdivisions: 0,
-            }),
-            MeshMaterial::Sphere => Mesh::from(shape::UVSphere {
+            },
+            MeshMaterial::Sphere => ProceduralMesh::Sphere {
                 radius: 1.0,
                 sectors: 10,
                 stacks: 10,
-            }),
+            },
+            MeshMaterial::Capsule => ProceduralMesh::Capsule {
+                radius: 0.5,
+                depth: 1.0,
+                rings: 0,
+                latitudes: 16,
+                longitudes: 32,
+            },
+            MeshMaterial::Cylinder => ProceduralMesh::Cylinder {
+                radius: 0.5,
+                height: 1.0,
+                resolution: 16,
+                segments: 1,
+            },
+            MeshMaterial::Torus => ProceduralMesh::Torus {
+                radius: 1.0,
+                ring_radius: 0.5,
+                subdivisions_segments: 32,
+                subdivisions_sides: 24,
+            },
+            MeshMaterial::Icosphere => ProceduralMesh::Icosphere {
+                radius: 1.0,
+                subdivisions: 5,
+            },
         }
     }
+
+    fn to_mesh(&self) -> Mesh {
+        self.default_params().to_mesh()
+    }
 }
 
 #[derive(PartialEq)]
@@ -488,6 +857,12 @@ impl ToString for Camera {
 #[derive(Event)]
 struct AddSimpleObject(SimpleObject);
 
+#[derive(Event)]
+struct ImportGltf(String);
+
+#[derive(Default, Resource)]
+struct GltfImports(Vec<(String, Handle<Gltf>)>);
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, AppLabel)]
 pub struct TestSubApp;
 
@@ -496,23 +871,40 @@ static mut LOAD_FLAG: bool = false;
 impl Plugin for EditorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<AssetManagement>()
+            .init_resource::<AssetLoadSummary>()
+            .init_resource::<AssetFolderFiles>()
+            .init_resource::<DraggedAsset>()
             .init_resource::<EditorState>()
             //.init_resource::<UiRegistry>()
             .init_resource::<AssetRegistry>()
             .init_resource::<AssetSourceList>()
             .init_resource::<ScriptableRegistry>()
+            .init_resource::<ScriptWatcher>()
             .init_resource::<ComponentRegistry>()
+            .init_resource::<ScriptSystems>()
             .init_resource::<LogBuffer>()
             .init_resource::<LoadProjectProgress>()
+            .init_resource::<GltfImports>()
             .add_event::<LoadProject>()
             .add_event::<AddComponent>()
             .add_event::<LoadScript>()
+            .add_event::<AddScriptModule>()
+            .add_event::<RemoveScriptModule>()
             .add_event::<PreSaveProject>()
+            .add_event::<CleanUpAssets>()
             .add_event::<SaveProject>()
             .add_event::<SelectEntity>()
+            .add_event::<CopyEntity>()
+            .add_event::<PasteEntities>()
+            .add_event::<AssignAsset>()
+            .add_event::<ImportAsset>()
             .add_event::<AddSimpleObject>()
+            .add_event::<ImportGltf>()
             .add_event::<ResetWorldEvent>()
             .add_event::<StartPlaying>()
+            .add_event::<PausePlaying>()
+            .add_event::<StopPlaying>()
+            .init_schedule(ScriptUpdate)
             .add_plugins((EguiPlugin, WireframePlugin, LogPlugin))
             .add_plugins((Hierarchy, Inspector, Controls))
             //.add_plugins(bevy_mod_picking::DefaultPickingPlugins)
@@ -523,20 +915,42 @@ impl Plugin for EditorPlugin {
             .add_systems(Update, load_project)
             .add_systems(Update, load_project_step)
             .add_systems(Update, pre_save_project)
+            .add_systems(Update, clean_up_assets_on_event)
             .add_systems(Update, save_project)
             .add_systems(Update, select_entity)
+            .add_systems(Update, copy_entity_on_event)
+            .add_systems(Update, paste_entities_on_event)
+            .add_systems(Update, assign_asset_on_event)
+            .add_systems(Update, import_asset_on_event)
             .add_systems(Update, attach_assets)
             .add_systems(Update, add_simple_object)
+            .add_systems(Update, import_gltf)
+            .add_systems(Update, spawn_gltf_imports)
             // .add_systems(Update, update_ui_registry)
             //.add_systems(Update, || {})
             .add_systems(Update, load_scripts)
+            .add_systems(Update, script_modules_on_event)
+            .add_systems(
+                PreUpdate,
+                update_script_context.after(bevy::input::InputSystem),
+            )
             .add_systems(Update, process_scripts)
+            // stopped scripts must not run their systems in the same frame
+            .add_systems(
+                Update,
+                (handle_pause_and_stop, apply_deferred, run_script_systems)
+                    .chain()
+                    .after(process_scripts),
+            )
+            .add_systems(FixedUpdate, process_scripts_fixed)
             .add_systems(Update, add_components)
             .add_systems(Update, handle_tasks)
+            .add_systems(Update, watch_scripts)
             .add_systems(Update, show_popup_on_error)
             .add_systems(Update, handle_start_playing)
             .register_type::<Rect>()
             .register_type::<FixedWireframe>()
+            .register_type::<UnknownComponents>()
             .register_type_data::<FixedWireframe, ReflectSerialize>()
             .register_type_data::<FixedWireframe, ReflectDeserialize>()
             .register_type_data::<FixedWireframe, ReflectComponent>()
@@ -590,23 +1004,23 @@ fn ui_inspect(
                     world.send_event(PreSaveProject());
                     ui.close_menu();
                 }
+                if ui.button("Clean up assets").clicked() {
+                    world.send_event(CleanUpAssets);
+                    ui.close_menu();
+                }
+                if ui.button("Import asset").clicked() {
+                    world.resource_mut::<EditorState>().import_asset_popup_shown = true;
+                    ui.close_menu();
+                }
             });
             ui.menu_button("Insert", |ui| {
                 ui.menu_button("Object", |ui| {
-                    if ui.button("Cube").clicked() {
-                        world.send_event(AddSimpleObject(SimpleObject::MeshMaterial(
-                            MeshMaterial::Cube,
-                        )));
-                    }
-                    if ui.button("Plane").clicked() {
-                        world.send_event(AddSimpleObject(SimpleObject::MeshMaterial(
-                            MeshMaterial::Plane,
-                        )));
-                    }
-                    if ui.button("Sphere").clicked() {
-                        world.send_event(AddSimpleObject(SimpleObject::MeshMaterial(
-                            MeshMaterial::Sphere,
-                        )));
+                    for mesh_material in MeshMaterial::ALL {
+                        if ui.button(mesh_material.to_string()).clicked() {
+                            world.send_event(AddSimpleObject(SimpleObject::MeshMaterial(
+                                mesh_material,
+                            )));
+                        }
                     }
                 });
                 ui.menu_button("Light", |ui| {
@@ -623,6 +1037,21 @@ fn ui_inspect(
                         world.send_event(AddSimpleObject(SimpleObject::Light(Light::Ambient)));
                     }
                 });
+                ui.menu_button("glTF", |ui| {
+                    // assets folder is scanned once, see `asset_folder_files`
+                    let files = asset_folder_files(world)
+                        .into_iter()
+                        .filter(|file| AssetFileKind::of(file) == AssetFileKind::Gltf)
+                        .collect::<Vec<_>>();
+                    if files.is_empty() {
+                        ui.label("No glTF files in assets folder");
+                    }
+                    for file in files {
+                        if ui.button(file.as_str()).clicked() {
+                            world.send_event(ImportGltf(file));
+                        }
+                    }
+                });
                 ui.menu_button("Camera", |ui| {
                     if ui.button("Perspective").clicked() {
                         world
@@ -635,6 +1064,21 @@ fn ui_inspect(
                     }
                 });
             });
+            ui.menu_button("Settings", |ui| {
+                let mut script_watcher = world.resource_mut::<ScriptWatcher>();
+                ui.checkbox(&mut script_watcher.enabled, "Rebuild scripts on change");
+                let mut registry = world.resource_mut::<ScriptableRegistry>();
+                let mut timeout = registry.build_timeout.as_secs();
+                ui.horizontal(|ui| {
+                    ui.label("Script build timeout (s)");
+                    if ui
+                        .add(egui::DragValue::new(&mut timeout).clamp_range(10..=3600))
+                        .changed()
+                    {
+                        registry.build_timeout = Duration::from_secs(timeout);
+                    }
+                });
+            });
         });
     });
 
@@ -642,31 +1086,46 @@ fn ui_inspect(
         {
             Hierarchy::ui(ui, world);
         }
-        world.resource_scope(|world, editor_state: Mut<EditorState>| {
+        world.resource_scope(|world, mut editor_state: Mut<EditorState>| {
             ui.separator();
             ui.label("Scripts");
             ui.separator();
-            if let Some(project) = &editor_state.current_project {
-                // currently, only one script per project is available
-                // script === dynamic lib from project dir
-                let path = Path::new(&project.project_description.path).join("scripts");
-                if project.project_state.script_enabled {
-                    ui.horizontal(|ui| {
-                        ui.label("Script (dylib)");
-                        if ui.button("⟲").clicked() {
-                            world.send_event(LoadScript(path.display().to_string(), true))
-                        }
-                        if ui.button("❌").clicked() {
-                            world.resource_scope(|world, mut reg: Mut<ScriptableRegistry>| {
-                                reg.old_impls.clear();
-                            });
-                            // TODO remove script
-                        }
-                    });
-                } else if ui.button("Load script ➕").clicked() {
-                    world.send_event(LoadScript(path.display().to_string(), false))
-                }
+            let Some(project) = &editor_state.current_project else {
+                return;
+            };
+            // script module === dynamic lib of a crate in the project dir
+            for module in project.script_modules() {
+                let path = project.script_path(&module).display().to_string();
+                let registry = world.resource::<ScriptableRegistry>();
+                let status = match registry.status(&path) {
+                    Some(BuildStatus::Failed) if registry.is_loaded(&path) => {
+                        "failed, previous version loaded".to_string()
+                    }
+                    Some(status) => status.to_string(),
+                    None => "not loaded".to_string(),
+                };
+                let systems = registry.system_names(&path).join(", ");
+                ui.horizontal(|ui| {
+                    let label = ui.label(format!("{} ({})", module, status));
+                    if !systems.is_empty() {
+                        label.on_hover_text(format!("Systems: {}", systems));
+                    }
+                    if ui.button("⟲").clicked() {
+                        world.send_event(LoadScript(path.clone(), true))
+                    }
+                    if ui.button("❌").clicked() {
+                        world.send_event(RemoveScriptModule(module.clone()));
+                    }
+                });
             }
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut editor_state.new_script_module_name);
+                if ui.button("Add script ➕").clicked() {
+                    let module = editor_state.new_script_module_name.trim().to_string();
+                    editor_state.new_script_module_name.clear();
+                    world.send_event(AddScriptModule(module));
+                }
+            });
         });
     });
 
@@ -676,7 +1135,11 @@ fn ui_inspect(
                 .query_filtered::<(Entity, Option<&Name>), With<SelectedEntity>>()
                 .get_single_mut(world)
             {
+                let asset_choices = asset_choices(world);
+                world.insert_resource(asset_choices);
                 Inspector::ui(ui, world);
+                procedural_mesh_ui(ui, world, entity);
+                material_ui(ui, world, entity);
                 ui.separator();
                 ui.menu_button("Add component ➕", |ui| {
                     // TODO add fixed elements (if not already on entity) (transform, light, etc.) besides script components
@@ -693,6 +1156,12 @@ fn ui_inspect(
         });
     });
 
+    if let Some((fraction, text)) = load_progress(world) {
+        egui::TopBottomPanel::top("load_progress").show(egui_context, |ui| {
+            ui.add(egui::ProgressBar::new(fraction).text(text).animate(true));
+        });
+    }
+
     egui::TopBottomPanel::top("controls").show(egui_context, |ui| {
         world.resource_scope(|world, editor_state: Mut<EditorState>| {
             if editor_state.current_project.is_some() {
@@ -701,11 +1170,47 @@ fn ui_inspect(
         });
     });
 
+    let build_problems = world
+        .resource::<ScriptableRegistry>()
+        .build_problems()
+        .collect::<Vec<_>>();
+    if !build_problems.is_empty() {
+        egui::TopBottomPanel::bottom("build_problems").show(egui_context, |ui| {
+            build_problems_ui(ui, &build_problems);
+        });
+    }
+
     egui::TopBottomPanel::bottom("logs").show(egui_context, |ui| {
         let log_buffer = world.resource::<LogBuffer>();
         logs_ui(ui, log_buffer);
     });
 
+    if world.resource::<EditorState>().current_project.is_some() {
+        egui::TopBottomPanel::bottom("assets")
+            .resizable(true)
+            .show(egui_context, |ui| {
+                let groups = asset_browser_groups(world);
+                let response = asset_browser(ui, &groups);
+                if response.refresh {
+                    world.resource_mut::<AssetFolderFiles>().0 = None;
+                }
+                if let Some(entity) = response.selected {
+                    world.send_event(SelectEntity(entity));
+                }
+                if let Some(AssetRef::Source(handle_id)) = response.toggle_pin {
+                    for source in world.resource_mut::<AssetSourceList>().0.iter_mut() {
+                        if source.handle_id() == handle_id {
+                            source.pinned = !source.pinned;
+                        }
+                    }
+                }
+                // hierarchy takes the dragged asset on drop, clear it when drag is cancelled
+                if response.dragged.is_some() || !ui.memory(|m| m.is_anything_being_dragged()) {
+                    world.resource_mut::<DraggedAsset>().0 = response.dragged;
+                }
+            });
+    }
+
     world.resource_scope(|world, mut editor_state: Mut<EditorState>| {
         if let Some(popup) = &editor_state.current_popup {
             if show_popup(egui_context, popup) {
@@ -863,12 +1368,200 @@ fn ui_inspect(
                         });
                     });
             }
+        } else if editor_state.import_asset_popup_shown {
+            egui::Window::new("Import asset")
+                .collapsible(false)
+                .show(egui_context, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("File");
+                        ui.text_edit_singleline(&mut editor_state.import_asset_path);
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            editor_state.import_asset_popup_shown = false;
+                        }
+                        if ui.button("Import").clicked() {
+                            let path = PathBuf::from(editor_state.import_asset_path.trim());
+                            world.send_event(ImportAsset(path));
+                            editor_state.import_asset_popup_shown = false;
+                            editor_state.import_asset_path.clear();
+                        }
+                    });
+                });
         }
     });
     //});
     //let mut blank = std::mem::replace(&mut *egui_context_mut, dst);
 }
 
+/// Parameters of the selected entity's procedural mesh, changes regenerate the mesh in place
+/// (for all entities sharing it).
+fn procedural_mesh_ui(ui: &mut Ui, world: &mut World, entity: Entity) {
+    let Some(handle_id) = world.get::<Handle<Mesh>>(entity).map(|handle| handle.id()) else {
+        return;
+    };
+    let HandleId::Id(_, uid) = handle_id else {
+        return;
+    };
+    let type_uuid = Mesh::TYPE_UUID.to_string();
+    let Some(raw) = world
+        .resource::<AssetSourceList>()
+        .0
+        .iter()
+        .find(|source| source.uid == uid && source.type_uuid == type_uuid)
+        .and_then(|source| match &source.source_type {
+            AssetSourceType::AsString(raw) => Some(raw.clone()),
+            AssetSourceType::AsFile(_) => None,
+        })
+    else {
+        return;
+    };
+    let mut procedural_mesh = match raw.parse::<MeshMaterial>() {
+        Ok(mesh_material) => mesh_material.default_params(),
+        Err(_) => match ron::from_str::<ProceduralMesh>(&raw) {
+            Ok(result) => result,
+            // not a primitive (ex.: captured geometry)
+            Err(_) => return,
+        },
+    };
+
+    ui.separator();
+    ui.label(format!("Mesh - {}", procedural_mesh.name()));
+    if procedural_mesh.ui(ui) {
+        let new_raw = ron::to_string(&procedural_mesh).unwrap();
+        for source in world.resource_mut::<AssetSourceList>().0.iter_mut() {
+            if source.uid == uid && source.type_uuid == type_uuid {
+                source.source_type = AssetSourceType::AsString(new_raw.clone());
+            }
+        }
+        world
+            .resource_mut::<Assets<Mesh>>()
+            .set_untracked(handle_id, procedural_mesh.to_mesh());
+    }
+}
+
+/// Sharing of the selected entity's material. Named materials form a library, that can be
+/// assigned to any entity.
+fn material_ui(ui: &mut Ui, world: &mut World, entity: Entity) {
+    let Some(handle_id) = world
+        .get::<Handle<StandardMaterial>>(entity)
+        .map(|handle| handle.id())
+    else {
+        return;
+    };
+    let share_count = world
+        .query::<&Handle<StandardMaterial>>()
+        .iter(world)
+        .filter(|handle| handle.id() == handle_id)
+        .count();
+    let type_uuid = StandardMaterial::TYPE_UUID.to_string();
+    let sources = &world.resource::<AssetSourceList>().0;
+    let source_name = sources
+        .iter()
+        .find(|source| source.handle_id() == handle_id)
+        .map(|source| source.name.clone());
+    let library = sources
+        .iter()
+        .filter(|source| source.type_uuid == type_uuid)
+        .filter_map(|source| Some((source.name.clone()?, source.handle_id())))
+        .collect::<Vec<_>>();
+
+    ui.separator();
+    match &source_name {
+        Some(Some(name)) => ui.label(format!("Material - {}", name)),
+        _ => ui.label("Material"),
+    };
+    if share_count > 1 {
+        ui.horizontal(|ui| {
+            ui.label(format!("Shared by {} entities", share_count));
+            if ui.button("Make unique").clicked() {
+                make_material_unique(world, entity);
+            }
+        });
+    }
+    if let Some(None) = source_name {
+        ui.horizontal(|ui| {
+            let mut editor_state = world.resource_mut::<EditorState>();
+            ui.text_edit_singleline(&mut editor_state.new_material_name);
+            let name = editor_state.new_material_name.trim().to_string();
+            if ui.button("Add to library").clicked() && !name.is_empty() {
+                editor_state.new_material_name.clear();
+                for source in world.resource_mut::<AssetSourceList>().0.iter_mut() {
+                    if source.handle_id() == handle_id {
+                        source.name = Some(name.clone());
+                    }
+                }
+            }
+        });
+    }
+    if !library.is_empty() {
+        let selected_text = match &source_name {
+            Some(Some(name)) => name.clone(),
+            _ => "-".to_string(),
+        };
+        egui::ComboBox::from_label("Library")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (name, library_handle_id) in &library {
+                    if ui
+                        .selectable_label(*library_handle_id == handle_id, name.as_str())
+                        .clicked()
+                    {
+                        let handle = world
+                            .resource::<Assets<StandardMaterial>>()
+                            .get_handle(*library_handle_id);
+                        world.entity_mut(entity).insert(handle);
+                    }
+                }
+            });
+    }
+}
+
+/// Gives the entity its own copy of its material, registered as a new asset source.
+fn make_material_unique(world: &mut World, entity: Entity) {
+    let Some(handle) = world.get::<Handle<StandardMaterial>>(entity).cloned() else {
+        return;
+    };
+    let Some(material) = world
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .cloned()
+    else {
+        return;
+    };
+    let raw = match AssetSourceable::to_string(&material, String::new(), world) {
+        Ok(raw) => raw,
+        Err(e) => {
+            world.send_event(PushLog(
+                format!("ASSET::MAKE_UNIQUE::FAILED -> {}", e),
+                Level::Error,
+            ));
+            return;
+        }
+    };
+
+    let source = world.resource_scope(|world, mut sources: Mut<AssetSourceList>| {
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        sources.add(
+            AssetSourceType::AsString(raw),
+            StandardMaterial::TYPE_UUID,
+            |handle_id| materials.contains(&Handle::weak(handle_id)),
+        )
+    });
+    let handle_id = source.handle_id();
+    let new_handle = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .set(handle_id, material);
+    world.resource_mut::<AssetManagement>().push(AssetEntry {
+        source,
+        original: new_handle.clone_untyped(),
+        attached: Some(new_handle.clone_untyped()),
+        failed: false,
+    });
+    world.entity_mut(entity).insert(new_handle);
+}
+
 #[derive(Event)]
 struct AddComponent(Entity, TypeId);
 
@@ -901,15 +1594,15 @@ fn load_project(
         println!("LOAD PROJECT");
 
         let project: Project = event.0.clone();
-        editor_state.current_file_explorer_path =
-            PathBuf::from(project.project_description.path.clone());
         editor_state.current_project = Some(project);
 
-        if event.0.project_state.script_enabled {
+        if !event.0.script_modules().is_empty() {
             load_project_progress.0 = LoadProjectStep::Scripts(false);
 
-            let path = Path::new(&event.0.project_description.path).join("scripts");
-            commands.add(AttachScript(path.display().to_string(), false));
+            for module in event.0.script_modules() {
+                let path = event.0.script_path(&module);
+                commands.add(AttachScript(path.display().to_string(), false));
+            }
         } else {
             load_project_progress.0 = LoadProjectStep::Scripts(true);
         }
@@ -924,7 +1617,11 @@ fn load_project_step(
     mut editor_state: ResMut<EditorState>,
     mut load_project_progress: ResMut<LoadProjectProgress>,
     mut asset_source_list: ResMut<AssetSourceList>,
-    asset_server: Res<AssetServer>,
+    asset_management: Res<AssetManagement>,
+    mut asset_load_summary: ResMut<AssetLoadSummary>,
+    mut ev_push_log: EventWriter<PushLog>,
+    mut dynamic_scenes: ResMut<Assets<DynamicScene>>,
+    type_registry: Res<AppTypeRegistry>,
     mut commands: Commands,
 ) {
     if let Some(project) = &editor_state.current_project {
@@ -943,16 +1640,22 @@ fn load_project_step(
                             .join("scenes")
                             .join(project.project_state.asset_file.clone());
 
-                    let asset_entries: Vec<AssetSource> = ron::from_str(
-                        std::fs::read_to_string(project_asset_path)
-                            .unwrap()
-                            .as_str(),
-                    )
-                    .unwrap();
+                    let asset_entries = match read_asset_sources(&project_asset_path) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            error!("FAILED to load asset sources! {}", e);
+                            ev_push_log.send(PushLog(e.to_string(), Level::Error));
+                            vec![]
+                        }
+                    };
 
                     println!("{:?}", asset_entries);
 
                     let asset_count = asset_entries.len();
+                    *asset_load_summary = AssetLoadSummary {
+                        total: asset_count,
+                        failed: vec![],
+                    };
                     for entry in asset_entries {
                         asset_source_list.0.push(entry.clone());
                         commands.add(LoadAsset(entry));
@@ -962,8 +1665,28 @@ fn load_project_step(
                 }
             }
             LoadProjectStep::Assets(left) => {
-                if left == 0 {
+                // file assets are loaded by the AssetServer, scene must wait until they are
+                // attached to their stored uids
+                let pending = asset_management
+                    .iter()
+                    .filter(|entry| entry.attached.is_none() && !entry.failed)
+                    .count();
+                if left == 0 && pending == 0 {
                     info!("STEP - Finished loading assets");
+                    let failed_count = asset_load_summary.failed.len();
+                    ev_push_log.send(PushLog(
+                        format!(
+                            "Loaded {} of {} asset(s), {} failed and replaced by placeholders",
+                            asset_load_summary.total.saturating_sub(failed_count),
+                            asset_load_summary.total,
+                            failed_count
+                        ),
+                        if failed_count == 0 {
+                            Level::Info
+                        } else {
+                            Level::Warn
+                        },
+                    ));
                     info!("STEP - Started loading scene");
                     let project_scene_path =
                         Path::new(project.project_description.path.as_os_str())
@@ -971,34 +1694,29 @@ fn load_project_step(
                             .join(project.project_state.scene_file.clone());
 
                     println!("loading {}", project_scene_path.to_str().unwrap());
-                    let handle = asset_server.load(project_scene_path);
-                    load_project_progress.0 = LoadProjectStep::Scene(handle, false);
+                    // Scene is not loaded through AssetServer, as components with unregistered
+                    // types (ex.: script failed to build) must be split out before deserializing
+                    match load_scene_file(&project_scene_path, &type_registry) {
+                        Ok((scene, unknown_components)) => {
+                            let handle = dynamic_scenes.add(scene);
+                            commands.add(LoadScene(handle.clone(), unknown_components));
+                            load_project_progress.0 = LoadProjectStep::Scene(handle, false);
+                        }
+                        Err(e) => {
+                            error!("FAILED to load scene! {}", e);
+                            load_project_progress.0 = LoadProjectStep::Done;
+                        }
+                    }
                 } else {
-                    info!("STEP - Progress loading assets {} left", left);
+                    info!(
+                        "STEP - Progress loading assets {} left, {} pending",
+                        left, pending
+                    );
                 }
             }
-            LoadProjectStep::Scene(ref handle, done) => {
+            LoadProjectStep::Scene(_, done) => {
                 if !done {
                     info!("STEP - Progress loading scene");
-                    use bevy::asset::LoadState;
-
-                    match asset_server.get_load_state(handle.id()) {
-                        LoadState::Failed => {
-                            println!("FAILED to load scene! {:?}", handle);
-                            // one of our assets had an error
-                            load_project_progress.0 = LoadProjectStep::Scene(handle.clone(), true);
-                        }
-                        LoadState::Loaded => {
-                            // all assets are now ready
-                            println!("Success - loaded scene, will attach! {:?}", handle);
-                            commands.add(LoadScene(handle.clone()));
-
-                            load_project_progress.0 = LoadProjectStep::Scene(handle.clone(), true);
-                        }
-                        _ => {
-                            // NotLoaded/Loading: not fully ready yet
-                        }
-                    }
                 } else {
                     info!("STEP - Finished loading scene");
                     load_project_progress.0 = LoadProjectStep::Done;
@@ -1011,6 +1729,84 @@ fn load_project_step(
     }
 }
 
+fn read_asset_sources(path: &Path) -> EResult<Vec<AssetSource>> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(result) => result,
+        Err(e) => bail!("ASSET::LOAD::CANNOT_READ_FILE", e),
+    };
+    match ron::from_str(source.as_str()) {
+        Ok(result) => Ok(result),
+        Err(e) => bail!("ASSET::LOAD::CANNOT_PARSE_FILE", e),
+    }
+}
+
+/// Fraction and description of the current project load step, `None` if nothing is loading.
+fn load_progress(world: &World) -> Option<(f32, String)> {
+    match &world.resource::<LoadProjectProgress>().0 {
+        LoadProjectStep::None | LoadProjectStep::Done => None,
+        LoadProjectStep::Scripts(_) => Some((0.0, "Building scripts".to_string())),
+        LoadProjectStep::Assets(left) => {
+            let total = world.resource::<AssetLoadSummary>().total;
+            let pending = world
+                .resource::<AssetManagement>()
+                .iter()
+                .filter(|entry| entry.attached.is_none() && !entry.failed)
+                .count();
+            let done = total.saturating_sub(left + pending);
+            let fraction = if total == 0 {
+                1.0
+            } else {
+                done as f32 / total as f32
+            };
+            Some((
+                0.1 + 0.8 * fraction,
+                format!("Loading assets {}/{}", done, total),
+            ))
+        }
+        LoadProjectStep::Scene(_, _) => Some((0.9, "Loading scene".to_string())),
+    }
+}
+
+fn load_scene_file(
+    path: &Path,
+    type_registry: &AppTypeRegistry,
+) -> EResult<(DynamicScene, HashMap<u64, Vec<(String, String)>>)> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(result) => result,
+        Err(e) => bail!("SCENE::LOAD::CANNOT_READ_FILE", e),
+    };
+
+    deserialize_scene(&source, type_registry)
+}
+
+fn deserialize_scene(
+    source: &str,
+    type_registry: &AppTypeRegistry,
+) -> EResult<(DynamicScene, HashMap<u64, Vec<(String, String)>>)> {
+    let type_registry = type_registry.read();
+    let (source, unknown_components) = split_unknown_components(source, &type_registry);
+    for (entity, components) in unknown_components.iter() {
+        for (type_name, _) in components {
+            warn!(
+                "Unknown component {} on entity {}, kept as raw",
+                type_name, entity
+            );
+        }
+    }
+
+    let mut deserializer = match ron::de::Deserializer::from_str(&source) {
+        Ok(result) => result,
+        Err(e) => bail!("SCENE::LOAD::CANNOT_PARSE", e),
+    };
+    let scene_deserializer = SceneDeserializer {
+        type_registry: &type_registry,
+    };
+    match scene_deserializer.deserialize(&mut deserializer) {
+        Ok(scene) => Ok((scene, unknown_components)),
+        Err(e) => bail!("SCENE::LOAD::CANNOT_DESERIALIZE", e),
+    }
+}
+
 fn pre_save_project(
     mut ev_pre_save_project: EventReader<PreSaveProject>,
     query: Query<Entity>,
@@ -1034,79 +1830,267 @@ struct SaveProjectCommand;
 
 impl Command for SaveProjectCommand {
     fn apply(self, world: &mut World) {
+        register_path_handles::<Mesh>(world);
+        register_path_handles::<StandardMaterial>(world);
+        register_path_handles::<ColorMaterial>(world);
+        register_path_handles::<Image>(world);
+        register_path_handles::<AudioSource>(world);
+        clean_up_assets(world);
         world.send_event(SaveProject());
     }
 }
 
-fn save_project(
-    world: &World,
-    mut ev_save_project: EventReader<SaveProject>,
-    type_registry: Res<AppTypeRegistry>,
-    asset_registry: Res<AssetRegistry>,
-    asset_source_list: Res<AssetSourceList>,
-) {
-    // Only take one instance of LoadProject event - multiple events should not happen
-    if let Some(_) = ev_save_project.iter().next() {
-        if let Some(project) = &world.resource::<EditorState>().current_project {
-            let project_scene_path = Path::new(project.project_description.path.as_os_str())
-                .join("scenes")
-                .join(project.project_state.scene_file.clone());
+/// Handles loaded by path (ex.: `AssetServer::load` in a script) get a file asset source and are
+/// replaced by its handle, so the scene refers only to asset sources.
+fn register_path_handles<T: Asset>(world: &mut World) {
+    let path_handles = world
+        .query::<(Entity, &Handle<T>)>()
+        .iter(world)
+        .filter(|(_, handle)| matches!(handle.id(), HandleId::AssetPathId(_)))
+        .map(|(entity, handle)| (entity, handle.clone()))
+        .collect::<Vec<_>>();
+
+    for (entity, handle) in path_handles {
+        let file = world
+            .resource::<AssetServer>()
+            .get_handle_path(&handle)
+            .and_then(|path| {
+                let project = world.resource::<EditorState>().current_project.as_ref()?;
+                project.relative_asset_path(&path)
+            });
+        let Some(file) = file else {
+            world.send_event(PushLog(
+                format!("ASSET::PATH_HANDLE::OUTSIDE_ASSETS -> {:?}", handle.id()),
+                Level::Warn,
+            ));
+            continue;
+        };
+        let source = file_asset_source_of_type(world, &file, T::TYPE_UUID);
+        let handle = world
+            .resource::<AssetServer>()
+            .get_handle::<T, _>(source.handle_id());
+        world.entity_mut(entity).insert(handle);
+    }
+}
 
-            let project_asset_path = Path::new(project.project_description.path.as_os_str())
-                .join("scenes")
-                .join(project.project_state.asset_file.clone());
+fn clean_up_assets_on_event(mut commands: Commands, mut events: EventReader<CleanUpAssets>) {
+    if events.iter().next().is_some() {
+        commands.add(|world: &mut World| clean_up_assets(world));
+    }
+}
 
-            let project_file_path =
-                Path::new(project.project_description.path.as_os_str()).join("project.bv");
+/// Removes asset sources that no entity or kept asset references (and are not pinned or in the
+/// library), reports them in logs.
+fn clean_up_assets(world: &mut World) {
+    let removed = remove_unused_asset_sources(world);
+    if removed.is_empty() {
+        return;
+    }
+    for source in removed.iter() {
+        info!("Removed unused asset source {:?}", source);
+    }
+    world.send_event(PushLog(
+        format!("Removed {} unused asset source(s)", removed.len()),
+        Level::Info,
+    ));
+}
 
-            println!(
-                "SAVE PROJECT {:?} - {:?} - {:?}",
-                project_scene_path, project_asset_path, project_file_path
-            );
+fn remove_unused_asset_sources(world: &mut World) -> Vec<AssetSource> {
+    let mut used = HashSet::new();
+    for entity in crate::core::to_dynamic_scene(world).entities {
+        for component in entity.components.iter() {
+            collect_handle_ids(&**component, &mut used);
+        }
+    }
+    // components of unloaded scripts are kept as raw RON, handles are found in the text
+    for components in world
+        .iter_entities()
+        .filter_map(|entity| entity.get::<UnknownComponents>())
+    {
+        for (_, raw) in components.0.iter() {
+            collect_raw_handle_ids(raw, &mut used);
+        }
+    }
 
-            let scene = crate::core::to_dynamic_scene(world);
-            let mut scene_serialized = scene.serialize_ron(&type_registry).unwrap();
-
-            // TODO weird hack for transforming Rect type for area in OrthographicProjection
-            loop {
-                let re = Regex::new(r"area: \(\n +min: \(\n +x: ([+-]?\d+\.?\d*),\n +y: ([+-]?\d+\.?\d*),\n +\),\n +max: \(\n +x: ([+-]?\d+\.?\d*),\n +y: ([+-]?\d+\.?\d*),\n +\),\n +\),").unwrap();
-                if let Some(caps) = re.captures(&scene_serialized) {
-                    println!("reformat rect");
-                    let replacement = r"area: (
-            min: (X1, Y1),
-            max: (X2, Y2),
-          ),";
-                    let new_val = replacement
-                        .replace("X1", caps.get(1).unwrap().into())
-                        .replace("Y1", caps.get(2).unwrap().into())
-                        .replace("X2", caps.get(3).unwrap().into())
-                        .replace("Y2", caps.get(4).unwrap().into());
-
-                    scene_serialized = re.replace(&scene_serialized, new_val).to_string();
-                } else {
-                    break;
-                }
-            }
+    // current content of the sources, ex.: textures assigned since the last save
+    let mut sources = world.resource::<AssetSourceList>().0.clone();
+    if let Some(asset_registry) = world.get_resource::<AssetRegistry>() {
+        for source in sources.iter_mut() {
+            asset_registry.update_source(source, world);
+        }
+    }
+    let kept = kept_asset_sources(&sources, &used);
 
-            std::fs::write(project_scene_path, scene_serialized).unwrap();
+    let mut removed = vec![];
+    world.resource_mut::<AssetSourceList>().0.retain(|source| {
+        let keep = kept.contains(&source.handle_id());
+        if !keep {
+            removed.push(source.clone());
+        }
+        keep
+    });
 
-            let mut source_list_clone = asset_source_list.0.clone();
-            for source in source_list_clone.as_mut_slice() {
-                asset_registry.update_source(source, world);
-            }
-            let assets_serialized = serialize_ron(&source_list_clone).unwrap();
-            std::fs::write(project_asset_path, assets_serialized).unwrap();
+    world.resource_mut::<AssetManagement>().retain(|entry| {
+        !removed.iter().any(|source| {
+            source.uid == entry.source.uid && source.type_uuid == entry.source.type_uuid
+        })
+    });
+    removed
+}
 
-            let file_serialized = serde_json::to_string(&project).unwrap();
-            std::fs::write(project_file_path, file_serialized).unwrap();
+/// Sources that are pinned, in the library or `used`, with files they reference (ex.: textures of
+/// a material).
+fn kept_asset_sources(sources: &[AssetSource], used: &HashSet<HandleId>) -> HashSet<HandleId> {
+    let mut kept = HashSet::new();
+    let mut pending = sources
+        .iter()
+        .filter(|source| {
+            source.pinned || source.name.is_some() || used.contains(&source.handle_id())
+        })
+        .collect::<Vec<_>>();
+    while let Some(source) = pending.pop() {
+        if !kept.insert(source.handle_id()) {
+            continue;
+        }
+        for file in asset_source_file_references(source) {
+            let file = AssetSourceType::AsFile(file);
+            pending.extend(sources.iter().filter(|other| other.source_type == file));
         }
     }
+    kept
+}
+
+/// Files in the assets folder that the content of a source refers to.
+fn asset_source_file_references(source: &AssetSource) -> Vec<String> {
+    let AssetSourceType::AsString(raw) = &source.source_type else {
+        return vec![];
+    };
+    let Ok(type_uuid) = Uuid::from_str(&source.type_uuid) else {
+        return vec![];
+    };
+    if type_uuid == StandardMaterial::TYPE_UUID {
+        ron::from_str::<MaterialData>(raw)
+            .map(|material_data| material_data.texture_paths())
+            .unwrap_or_default()
+    } else if type_uuid == ColorMaterial::TYPE_UUID {
+        ron::from_str::<ColorMaterialData>(raw)
+            .map(|material_data| material_data.texture.into_iter().collect())
+            .unwrap_or_default()
+    } else {
+        vec![]
+    }
+}
+
+// handle ids in raw RON are serialized as `Id("<type uuid>", <uid>)`
+fn raw_handle_id_regex() -> Regex {
+    Regex::new(r#"\bId\(\s*"([0-9a-fA-F-]+)"\s*,\s*(\d+)\s*,?\s*\)"#).unwrap()
+}
+
+fn raw_handle_id(caps: &regex::Captures) -> Option<HandleId> {
+    let type_uuid = Uuid::from_str(&caps[1]).ok()?;
+    let uid = caps[2].parse::<u64>().ok()?;
+    Some(HandleId::Id(type_uuid, uid))
+}
+
+fn collect_raw_handle_ids(raw: &str, handle_ids: &mut HashSet<HandleId>) {
+    for caps in raw_handle_id_regex().captures_iter(raw) {
+        if let Some(handle_id) = raw_handle_id(&caps) {
+            handle_ids.insert(handle_id);
+        }
+    }
+}
+
+fn remap_raw_handle_ids(raw: &str, handle_ids: &HashMap<HandleId, HandleId>) -> String {
+    raw_handle_id_regex()
+        .replace_all(raw, |caps: &regex::Captures| {
+            match raw_handle_id(caps).and_then(|handle_id| handle_ids.get(&handle_id)) {
+                Some(HandleId::Id(type_uuid, uid)) => format!("Id(\"{}\", {})", type_uuid, uid),
+                _ => caps[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+fn save_project(mut commands: Commands, mut ev_save_project: EventReader<SaveProject>) {
+    // Only take one instance of LoadProject event - multiple events should not happen
+    if let Some(_) = ev_save_project.iter().next() {
+        commands.add(|world: &mut World| {
+            if let Err(e) = write_project_files(world) {
+                world.send_event(PushLog(e.to_string(), Level::Error));
+            }
+        });
+    }
 
     if ev_save_project.iter().next().is_some() {
         warn!("Multiple SaveProject events found in listener! Should not happen");
     }
 }
 
+fn write_project_files(world: &World) -> EResult<()> {
+    let Some(project) = &world.resource::<EditorState>().current_project else {
+        return Ok(());
+    };
+    let project_scene_path = Path::new(project.project_description.path.as_os_str())
+        .join("scenes")
+        .join(project.project_state.scene_file.clone());
+
+    let project_asset_path = Path::new(project.project_description.path.as_os_str())
+        .join("scenes")
+        .join(project.project_state.asset_file.clone());
+
+    let project_file_path =
+        Path::new(project.project_description.path.as_os_str()).join("project.bv");
+
+    println!(
+        "SAVE PROJECT {:?} - {:?} - {:?}",
+        project_scene_path, project_asset_path, project_file_path
+    );
+
+    let scene = crate::core::to_dynamic_scene(world);
+    let mut scene_serialized = match scene.serialize_ron(world.resource::<AppTypeRegistry>()) {
+        Ok(result) => fix_serialized_rect(result),
+        Err(e) => bail!("PROJECT::SAVE::CANNOT_SERIALIZE_SCENE", e),
+    };
+
+    let unknown_components = world
+        .iter_entities()
+        .filter_map(|entity| {
+            entity
+                .get::<UnknownComponents>()
+                .map(|components| (entity.id().to_bits(), components))
+        })
+        .collect::<Vec<_>>();
+    if !unknown_components.is_empty() {
+        scene_serialized = inject_unknown_components(&scene_serialized, &unknown_components);
+    }
+
+    if let Err(e) = std::fs::write(project_scene_path, scene_serialized) {
+        bail!("PROJECT::SAVE::CANNOT_WRITE_SCENE_FILE", e);
+    }
+
+    let asset_registry = world.resource::<AssetRegistry>();
+    let mut source_list_clone = world.resource::<AssetSourceList>().0.clone();
+    for source in source_list_clone.as_mut_slice() {
+        asset_registry.update_source(source, world);
+    }
+    let assets_serialized = match serialize_ron(&source_list_clone) {
+        Ok(result) => result,
+        Err(e) => bail!("PROJECT::SAVE::CANNOT_SERIALIZE_ASSETS", e),
+    };
+    if let Err(e) = std::fs::write(project_asset_path, assets_serialized) {
+        bail!("PROJECT::SAVE::CANNOT_WRITE_ASSET_FILE", e);
+    }
+
+    let file_serialized = match serde_json::to_string(&project) {
+        Ok(result) => result,
+        Err(e) => bail!("PROJECT::SAVE::CANNOT_SERIALIZE_PROJECT", e),
+    };
+    if let Err(e) = std::fs::write(project_file_path, file_serialized) {
+        bail!("PROJECT::SAVE::CANNOT_WRITE_PROJECT_FILE", e);
+    }
+    Ok(())
+}
+
 fn select_entity(
     mut commands: Commands,
     mut ev_select_entity: EventReader<SelectEntity>,
@@ -1129,37 +2113,767 @@ fn select_entity(
         warn!("Multiple SelectEntity events found in listener! Should not happen");
     }
 }
+/// Clipboard content for copied entities - scene with copied entities and asset sources they use.
+#[derive(Serialize, Deserialize)]
+struct ClipboardEntities {
+    scene: String,
+    assets: Vec<AssetSource>,
+}
+
+struct CopyEntityCommand(Entity);
+
+impl Command for CopyEntityCommand {
+    fn apply(self, world: &mut World) {
+        match copy_entity(world, self.0) {
+            Ok(serialized) => {
+                world
+                    .resource_mut::<EguiClipboard>()
+                    .set_contents(&serialized);
+                world.send_event(PushLog(
+                    "Copied entity to clipboard".to_string(),
+                    Level::Info,
+                ));
+            }
+            Err(e) => world.send_event(PushLog(e.to_string(), Level::Error)),
+        }
+    }
+}
+
+struct PasteEntitiesCommand;
+
+impl Command for PasteEntitiesCommand {
+    fn apply(self, world: &mut World) {
+        let Some(contents) = world.resource::<EguiClipboard>().get_contents() else {
+            return;
+        };
+        if let Err(e) = paste_entities(world, &contents) {
+            // clipboard can hold anything, do not bother with a popup
+            world.send_event(PushLog(e.to_string(), Level::Warn));
+        }
+    }
+}
+
+fn copy_entity(world: &World, root: Entity) -> EResult<String> {
+    let mut entities = vec![root];
+    let mut i = 0;
+    while i < entities.len() {
+        if let Some(children) = world.get::<Children>(entities[i]) {
+            entities.extend(children.iter());
+        }
+        i += 1;
+    }
+
+    let mut scene = crate::core::to_dynamic_scene_of(world, entities.iter().copied());
+    // Copied root is pasted at the top level, parent would point outside of copied entities
+    let parent_type_name = std::any::type_name::<Parent>();
+    let mut handle_ids = HashSet::new();
+    for entity in scene.entities.iter_mut() {
+        if entity.entity == root {
+            entity
+                .components
+                .retain(|component| component.type_name() != parent_type_name);
+        }
+        for component in entity.components.iter() {
+            collect_handle_ids(&**component, &mut handle_ids);
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let mut scene_serialized = match scene.serialize_ron(type_registry) {
+        Ok(result) => fix_serialized_rect(result),
+        Err(e) => bail!("CLIPBOARD::COPY::CANNOT_SERIALIZE_SCENE", e),
+    };
+    let unknown_components = entities
+        .iter()
+        .filter_map(|entity| {
+            world
+                .get::<UnknownComponents>(*entity)
+                .map(|components| (entity.to_bits(), components))
+        })
+        .collect::<Vec<_>>();
+    if !unknown_components.is_empty() {
+        scene_serialized = inject_unknown_components(&scene_serialized, &unknown_components);
+    }
+
+    let asset_registry = world.resource::<AssetRegistry>();
+    let mut assets = world
+        .resource::<AssetSourceList>()
+        .0
+        .iter()
+        .filter(|source| handle_ids.contains(&source.handle_id()))
+        .cloned()
+        .collect::<Vec<_>>();
+    for source in assets.iter_mut() {
+        asset_registry.update_source(source, world);
+    }
+
+    let clipboard = ClipboardEntities {
+        scene: scene_serialized,
+        assets,
+    };
+    match ron::to_string(&clipboard) {
+        Ok(result) => Ok(result),
+        Err(e) => bail!("CLIPBOARD::COPY::CANNOT_SERIALIZE", e),
+    }
+}
+
+fn paste_entities(world: &mut World, contents: &str) -> EResult<()> {
+    let clipboard: ClipboardEntities = match ron::from_str(contents) {
+        Ok(result) => result,
+        Err(e) => bail!("CLIPBOARD::PASTE::NOT_ENTITIES", e),
+    };
+
+    // uids are derived from content, so another project can use the same uid for a different
+    // asset (ex.: edited default material) - such sources get a new uid, handles are remapped
+    let mut remapped = HashMap::new();
+    for mut source in clipboard.assets {
+        let existing = world
+            .resource::<AssetSourceList>()
+            .0
+            .iter()
+            .find(|existing| existing.uid == source.uid && existing.type_uuid == source.type_uuid)
+            .cloned();
+        if let Some(mut existing) = existing {
+            // sources in the list are only updated on save
+            world
+                .resource::<AssetRegistry>()
+                .update_source(&mut existing, world);
+            if existing.source_type == source.source_type {
+                // same asset is already in the project (ex.: pasted to the same project)
+                continue;
+            }
+            let asset_registry = world.resource::<AssetRegistry>();
+            let old_handle_id = source.handle_id();
+            source.uid = allocate_uid(
+                &world.resource::<AssetSourceList>().0,
+                &source.type_uuid,
+                source_seed(&source.source_type),
+                |handle_id| asset_registry.contains(handle_id, world),
+            );
+            remapped.insert(old_handle_id, source.handle_id());
+        }
+        world
+            .resource_mut::<AssetSourceList>()
+            .0
+            .push(source.clone());
+        create_asset(world, &source);
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let (mut scene, mut unknown_components) = deserialize_scene(&clipboard.scene, &type_registry)?;
+    if !remapped.is_empty() {
+        for entity in scene.entities.iter_mut() {
+            for component in entity.components.iter_mut() {
+                remap_handle_ids(&mut **component, &remapped);
+            }
+        }
+        for (_, raw) in unknown_components.values_mut().flatten() {
+            *raw = remap_raw_handle_ids(raw, &remapped);
+        }
+    }
+
+    let mut entity_map = EntityMap::default();
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        bail!("CLIPBOARD::PASTE::CANNOT_WRITE_TO_WORLD", e);
+    }
+    let mut pasted: Vec<Entity> = entity_map.values().collect();
+    for (scene_entity, components) in unknown_components {
+        if let Some(entity) = entity_map.get(Entity::from_bits(scene_entity)) {
+            world
+                .entity_mut(entity)
+                .insert(UnknownComponents(components));
+        } else {
+            // entity had no known components
+            pasted.push(world.spawn(UnknownComponents(components)).id());
+        }
+    }
+    restore_skipped_components_of(world, &pasted);
+
+    let root = pasted
+        .iter()
+        .copied()
+        .find(|entity| world.get::<Parent>(*entity).is_none());
+    if let Some(root) = root {
+        world.send_event(SelectEntity(root));
+    }
+    Ok(())
+}
+
+fn collect_handle_ids(value: &dyn Reflect, handle_ids: &mut HashSet<HandleId>) {
+    if let Some(handle_id) = value.downcast_ref::<HandleId>() {
+        handle_ids.insert(*handle_id);
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value
+            .iter_fields()
+            .for_each(|field| collect_handle_ids(field, handle_ids)),
+        ReflectRef::TupleStruct(value) => value
+            .iter_fields()
+            .for_each(|field| collect_handle_ids(field, handle_ids)),
+        ReflectRef::Tuple(value) => value
+            .iter_fields()
+            .for_each(|field| collect_handle_ids(field, handle_ids)),
+        ReflectRef::List(value) => value
+            .iter()
+            .for_each(|field| collect_handle_ids(field, handle_ids)),
+        ReflectRef::Array(value) => value
+            .iter()
+            .for_each(|field| collect_handle_ids(field, handle_ids)),
+        ReflectRef::Map(value) => value
+            .iter()
+            .for_each(|(_, field)| collect_handle_ids(field, handle_ids)),
+        ReflectRef::Enum(value) => value
+            .iter_fields()
+            .for_each(|field| collect_handle_ids(field.value(), handle_ids)),
+        ReflectRef::Value(_) => {}
+    }
+}
+
+fn remap_handle_ids(value: &mut dyn Reflect, handle_ids: &HashMap<HandleId, HandleId>) {
+    if let Some(handle_id) = value.downcast_mut::<HandleId>() {
+        if let Some(new_handle_id) = handle_ids.get(handle_id) {
+            *handle_id = *new_handle_id;
+        }
+        return;
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => (0..value.field_len())
+            .for_each(|i| remap_handle_ids(value.field_at_mut(i).unwrap(), handle_ids)),
+        ReflectMut::TupleStruct(value) => (0..value.field_len())
+            .for_each(|i| remap_handle_ids(value.field_mut(i).unwrap(), handle_ids)),
+        ReflectMut::Tuple(value) => (0..value.field_len())
+            .for_each(|i| remap_handle_ids(value.field_mut(i).unwrap(), handle_ids)),
+        ReflectMut::List(value) => {
+            (0..value.len()).for_each(|i| remap_handle_ids(value.get_mut(i).unwrap(), handle_ids))
+        }
+        ReflectMut::Array(value) => {
+            (0..value.len()).for_each(|i| remap_handle_ids(value.get_mut(i).unwrap(), handle_ids))
+        }
+        ReflectMut::Map(value) => (0..value.len())
+            .for_each(|i| remap_handle_ids(value.get_at_mut(i).unwrap().1, handle_ids)),
+        ReflectMut::Enum(value) => (0..value.field_len())
+            .for_each(|i| remap_handle_ids(value.field_at_mut(i).unwrap(), handle_ids)),
+        ReflectMut::Value(_) => {}
+    }
+}
+
+fn copy_entity_on_event(mut commands: Commands, mut events: EventReader<CopyEntity>) {
+    for event in events.iter() {
+        commands.add(CopyEntityCommand(event.0));
+    }
+}
+
+fn paste_entities_on_event(mut commands: Commands, mut events: EventReader<PasteEntities>) {
+    for _ in events.iter() {
+        commands.add(PasteEntitiesCommand);
+    }
+}
+
+/// Files in the project's assets folder, scanned when the asset browser needs them.
+#[derive(Default, Resource)]
+struct AssetFolderFiles(Option<Vec<String>>);
+
+fn asset_browser_groups(world: &mut World) -> Vec<AssetGroup> {
+    let mut users: HashMap<HandleId, Vec<(Entity, String)>> = HashMap::new();
+    let mut mesh_query = world.query::<(Entity, &Handle<Mesh>, Option<&Name>)>();
+    for (entity, handle, name) in mesh_query.iter(world) {
+        users
+            .entry(handle.id())
+            .or_default()
+            .push((entity, entity_label(entity, name)));
+    }
+    let mut material_query = world.query::<(Entity, &Handle<StandardMaterial>, Option<&Name>)>();
+    for (entity, handle, name) in material_query.iter(world) {
+        users
+            .entry(handle.id())
+            .or_default()
+            .push((entity, entity_label(entity, name)));
+    }
+
+    let mut groups: Vec<AssetGroup> = vec![];
+    let mut push_item = |group_name: &str, item: AssetItem| match groups
+        .iter_mut()
+        .find(|group| group.name == group_name)
+    {
+        Some(group) => group.items.push(item),
+        None => groups.push(AssetGroup {
+            name: group_name.to_string(),
+            items: vec![item],
+        }),
+    };
+
+    let mut file_users: HashMap<String, Vec<(Entity, String)>> = HashMap::new();
+    for source in world.resource::<AssetSourceList>().0.iter() {
+        let handle_id = source.handle_id();
+        let source_users = users.get(&handle_id).cloned().unwrap_or_default();
+        if let AssetSourceType::AsFile(filepath) = &source.source_type {
+            let file = filepath.split('#').next().unwrap_or_default();
+            file_users
+                .entry(file.to_string())
+                .or_default()
+                .extend(source_users.iter().cloned());
+        }
+        let group_name = match Uuid::from_str(&source.type_uuid) {
+            Ok(uuid) if uuid == Mesh::TYPE_UUID => "Meshes",
+            Ok(uuid) if uuid == StandardMaterial::TYPE_UUID => "Materials",
+            Ok(uuid) if uuid == ColorMaterial::TYPE_UUID => "Materials",
+            Ok(uuid) if uuid == Image::TYPE_UUID => "Textures",
+            Ok(uuid) if uuid == AudioSource::TYPE_UUID => "Sounds",
+            Ok(uuid) if uuid == Font::TYPE_UUID => "Text fonts",
+            _ => "Other assets",
+        };
+        push_item(
+            group_name,
+            AssetItem {
+                label: source_label(source),
+                asset: AssetRef::Source(handle_id),
+                users: source_users,
+                pinned: Some(source.pinned),
+            },
+        );
+    }
+
+    for file in asset_folder_files(world) {
+        push_item(
+            AssetFileKind::of(file).group_name(),
+            AssetItem {
+                label: file.clone(),
+                asset: AssetRef::File(file.clone()),
+                users: file_users.remove(&file).unwrap_or_default(),
+                pinned: None,
+            },
+        );
+    }
+
+    groups
+}
+
+fn source_label(source: &AssetSource) -> String {
+    let label = match &source.source_type {
+        AssetSourceType::AsString(raw) if raw.chars().count() <= 48 => raw.clone(),
+        AssetSourceType::AsString(_) => format!("Procedural #{}", source.uid),
+        AssetSourceType::AsFile(filepath) => filepath.clone(),
+    };
+    match &source.name {
+        Some(name) => format!("{} ({})", name, label),
+        None => label,
+    }
+}
+
+fn asset_folder_files(world: &mut World) -> Vec<String> {
+    if world.resource::<AssetFolderFiles>().0.is_none() {
+        let files = match &world.resource::<EditorState>().current_project {
+            Some(project) => project.asset_files(&[]),
+            None => Ok(vec![]),
+        };
+        // not retried until the folder is rescanned
+        let files = files.unwrap_or_else(|e| {
+            world.send_event(PushLog(
+                format!("Cannot list asset files: {}", e),
+                Level::Error,
+            ));
+            vec![]
+        });
+        world.resource_mut::<AssetFolderFiles>().0 = Some(files);
+    }
+    world
+        .resource::<AssetFolderFiles>()
+        .0
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Asset sources and not yet used files of the assets folder, offered by handles in the inspector.
+fn asset_choices(world: &mut World) -> AssetChoices {
+    let mut options: HashMap<Uuid, Vec<(String, AssetRef)>> = HashMap::new();
+    let mut used_files = HashSet::new();
+    for source in world.resource::<AssetSourceList>().0.iter() {
+        let Ok(type_uuid) = Uuid::from_str(&source.type_uuid) else {
+            continue;
+        };
+        if let AssetSourceType::AsFile(filepath) = &source.source_type {
+            used_files.insert((type_uuid, filepath.clone()));
+        }
+        options
+            .entry(type_uuid)
+            .or_default()
+            .push((source_label(source), AssetRef::Source(source.handle_id())));
+    }
+    for file in asset_folder_files(world) {
+        let Some(type_uuid) = asset_file_type(&file) else {
+            continue;
+        };
+        if !used_files.contains(&(type_uuid, file.clone())) {
+            options
+                .entry(type_uuid)
+                .or_default()
+                .push((file.clone(), AssetRef::File(file)));
+        }
+    }
+    AssetChoices {
+        options,
+        resolve_file: |world, file| file_asset_source(world, file).map(|source| source.handle_id()),
+    }
+}
+
+fn entity_label(entity: Entity, name: Option<&Name>) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("Entity {}", entity.index()),
+    }
+}
+
+fn assign_asset_on_event(mut commands: Commands, mut events: EventReader<AssignAsset>) {
+    for event in events.iter() {
+        commands.add(AssignAssetCommand(event.0, event.1.clone()));
+    }
+}
+
+struct AssignAssetCommand(Entity, AssetRef);
+
+impl Command for AssignAssetCommand {
+    fn apply(self, world: &mut World) {
+        let handle_id = match self.1 {
+            AssetRef::Source(handle_id) => handle_id,
+            AssetRef::File(file) => match file_asset_source(world, &file) {
+                Some(source) => source.handle_id(),
+                None => {
+                    world.send_event(PushLog(
+                        format!("ASSET::ASSIGN::UNSUPPORTED_FILE -> {}", file),
+                        Level::Warn,
+                    ));
+                    return;
+                }
+            },
+        };
+
+        let HandleId::Id(type_uuid, _) = handle_id else {
+            return;
+        };
+        if world.get_entity(self.0).is_none() {
+            return;
+        }
+        // strong handles, so the asset is kept while the entity uses it
+        if type_uuid == Mesh::TYPE_UUID {
+            let handle = world.resource::<Assets<Mesh>>().get_handle(handle_id);
+            world.entity_mut(self.0).insert(handle);
+        } else if type_uuid == StandardMaterial::TYPE_UUID {
+            let handle = world
+                .resource::<Assets<StandardMaterial>>()
+                .get_handle(handle_id);
+            world.entity_mut(self.0).insert(handle);
+        } else {
+            world.send_event(PushLog(
+                "ASSET::ASSIGN::UNSUPPORTED_TYPE".to_string(),
+                Level::Warn,
+            ));
+        }
+    }
+}
+
+/// Returns the asset source for a file in the assets folder, a new one is created (and loaded)
+/// if the file is not used yet.
+fn file_asset_source(world: &mut World, file: &str) -> Option<AssetSource> {
+    let type_uuid = asset_file_type(file)?;
+    Some(file_asset_source_of_type(world, file, type_uuid))
+}
+
+fn file_asset_source_of_type(world: &mut World, file: &str, type_uuid: Uuid) -> AssetSource {
+    let mut sources = std::mem::take(&mut world.resource_mut::<AssetSourceList>().0);
+    let asset_registry = world.resource::<AssetRegistry>();
+    let (source, existing) = find_or_add_file_source(&mut sources, file, type_uuid, |handle_id| {
+        asset_registry.contains(handle_id, world)
+    });
+    world.resource_mut::<AssetSourceList>().0 = sources;
+    if !existing {
+        create_asset(world, &source);
+    }
+    source
+}
+
+/// Asset type a file in the assets folder is used as, `None` if it can only be copied.
+fn asset_file_type(file: &str) -> Option<Uuid> {
+    match AssetFileKind::of(file) {
+        // meshes use the first primitive by default, see `default_gltf_label`
+        AssetFileKind::Gltf => Some(Mesh::TYPE_UUID),
+        AssetFileKind::Image => Some(Image::TYPE_UUID),
+        AssetFileKind::Audio => Some(AudioSource::TYPE_UUID),
+        AssetFileKind::Font => Some(Font::TYPE_UUID),
+        AssetFileKind::Other => None,
+    }
+}
+
+/// Returns the source for the file and whether it already existed - existing sources keep uid.
+fn find_or_add_file_source(
+    sources: &mut Vec<AssetSource>,
+    file: &str,
+    type_uuid: Uuid,
+    is_live: impl Fn(HandleId) -> bool,
+) -> (AssetSource, bool) {
+    let source_type = AssetSourceType::AsFile(file.to_string());
+    let type_uuid = type_uuid.to_string();
+    if let Some(source) = sources
+        .iter()
+        .find(|source| source.type_uuid == type_uuid && source.source_type == source_type)
+    {
+        return (source.clone(), true);
+    }
+
+    let uid = allocate_uid(sources, &type_uuid, file, is_live);
+    let source = AssetSource {
+        source_type,
+        type_uuid,
+        uid,
+        pinned: false,
+        name: None,
+    };
+    sources.push(source.clone());
+    (source, false)
+}
+
+struct ImportedAsset {
+    // path relative to the assets folder
+    file: String,
+    // source and whether it already existed, `None` for files that are only copied
+    source: Option<(AssetSource, bool)>,
+}
+
+/// Copies an external file into the project's assets folder and adds an asset source for it.
+fn import_asset_file(
+    project: &Project,
+    external: &Path,
+    sources: &mut Vec<AssetSource>,
+    is_live: impl Fn(HandleId) -> bool,
+) -> EResult<ImportedAsset> {
+    if !external.is_file() {
+        bail!("ASSET::IMPORT::FILE_NOT_FOUND", external.display());
+    }
+    let Some(file) = external.file_name().and_then(|name| name.to_str()) else {
+        bail!("ASSET::IMPORT::INVALID_FILE_NAME", external.display());
+    };
+
+    let assets_path = project.assets_path();
+    let file = unique_asset_file_name(&assets_path, external, file);
+    let mut dependencies = vec![];
+    if AssetFileKind::of(&file) == AssetFileKind::Gltf {
+        for uri in gltf_external_uris(external)? {
+            let Some(parent) = external.parent() else {
+                continue;
+            };
+            let (from, to) = (parent.join(&uri), assets_path.join(&uri));
+            // referenced by the .gltf file, so it cannot be renamed
+            if to.exists() && !same_file_content(&from, &to) {
+                bail!("ASSET::IMPORT::FILE_EXISTS", uri);
+            }
+            dependencies.push((from, to));
+        }
+    }
+    copy_asset_file(external, &assets_path.join(&file))?;
+    for (from, to) in dependencies {
+        copy_asset_file(&from, &to)?;
+    }
+
+    let source = asset_file_type(&file)
+        .map(|type_uuid| find_or_add_file_source(sources, &file, type_uuid, &is_live));
+    Ok(ImportedAsset { file, source })
+}
+
+/// Name of the file in the assets folder, a different file with the same name is not
+/// overwritten (ex.: `texture.png` is imported as `texture_1.png`).
+fn unique_asset_file_name(assets_path: &Path, external: &Path, file: &str) -> String {
+    let (stem, extension) = match file.rsplit_once('.') {
+        Some((stem, extension)) => (stem, format!(".{}", extension)),
+        None => (file, String::new()),
+    };
+    let mut name = file.to_string();
+    let mut attempt = 1;
+    while assets_path.join(&name).exists() && !same_file_content(external, &assets_path.join(&name))
+    {
+        name = format!("{}_{}{}", stem, attempt, extension);
+        attempt += 1;
+    }
+    name
+}
+
+fn same_file_content(a: &Path, b: &Path) -> bool {
+    match (std::fs::read(a), std::fs::read(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn copy_asset_file(from: &Path, to: &Path) -> EResult<()> {
+    if same_file_content(from, to) {
+        // already in the assets folder
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            bail!("ASSET::IMPORT::CANNOT_CREATE_FOLDER", e);
+        }
+    }
+    if let Err(e) = std::fs::copy(from, to) {
+        bail!(
+            "ASSET::IMPORT::CANNOT_COPY_FILE",
+            format!("{} - {}", from.display(), e)
+        );
+    }
+    Ok(())
+}
+
+/// Buffers and images of a .gltf file, that are stored next to it (not embedded).
+fn gltf_external_uris(path: &Path) -> EResult<Vec<String>> {
+    if path
+        .extension()
+        .map_or(true, |extension| extension != "gltf")
+    {
+        return Ok(vec![]);
+    }
+    let content = match std::fs::read_to_string(path) {
+        Ok(result) => result,
+        Err(e) => bail!("ASSET::IMPORT::CANNOT_READ_GLTF", e),
+    };
+    let json: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(result) => result,
+        Err(e) => bail!("ASSET::IMPORT::CANNOT_PARSE_GLTF", e),
+    };
+
+    let mut uris = vec![];
+    for key in ["buffers", "images"] {
+        for item in json[key].as_array().into_iter().flatten() {
+            if let Some(uri) = item["uri"].as_str() {
+                if !uri.starts_with("data:") && !uri.contains("..") {
+                    uris.push(uri.to_string());
+                }
+            }
+        }
+    }
+    Ok(uris)
+}
+
+#[derive(Event)]
+struct ImportAsset(PathBuf);
+
+fn import_asset_on_event(mut commands: Commands, mut events: EventReader<ImportAsset>) {
+    for event in events.iter() {
+        commands.add(ImportAssetCommand(event.0.clone()));
+    }
+}
+
+struct ImportAssetCommand(PathBuf);
+
+impl Command for ImportAssetCommand {
+    fn apply(self, world: &mut World) {
+        let Some(project) = world.resource::<EditorState>().current_project.clone() else {
+            return;
+        };
+
+        let mut sources = std::mem::take(&mut world.resource_mut::<AssetSourceList>().0);
+        let asset_registry = world.resource::<AssetRegistry>();
+        let result = import_asset_file(&project, &self.0, &mut sources, |handle_id| {
+            asset_registry.contains(handle_id, world)
+        });
+        world.resource_mut::<AssetSourceList>().0 = sources;
+        world.resource_mut::<AssetFolderFiles>().0 = None;
+
+        let imported = match result {
+            Ok(result) => result,
+            Err(e) => {
+                world.send_event(PushLog(e.to_string(), Level::Error));
+                return;
+            }
+        };
+        // existing source means the same file was imported before and is already loaded
+        if let Some((source, false)) = &imported.source {
+            create_asset(world, source);
+        }
+        world.send_event(PushLog(
+            format!("Imported asset {}", imported.file),
+            Level::Info,
+        ));
+    }
+}
+
+/// Imports files into a project without running the editor,
+/// `bevytor_editor import <project path> <files>...`.
+pub fn import_assets_cli(project_path: &str, files: &[String]) -> EResult<()> {
+    let project = Project::load(ProjectDescription {
+        name: "".to_string(),
+        path: OsString::from(project_path),
+    })?;
+    let asset_file_path = Path::new(project.project_description.path.as_os_str())
+        .join("scenes")
+        .join(project.project_state.asset_file.clone());
+
+    let mut sources: Vec<AssetSource> = match std::fs::read_to_string(&asset_file_path) {
+        Ok(content) => match ron::from_str(&content) {
+            Ok(result) => result,
+            Err(e) => bail!("ASSET::IMPORT::CANNOT_PARSE_ASSET_FILE", e),
+        },
+        Err(e) => bail!("ASSET::IMPORT::CANNOT_READ_ASSET_FILE", e),
+    };
+
+    for file in files {
+        // no assets are loaded without the editor
+        let imported = import_asset_file(&project, Path::new(file), &mut sources, |_| false)?;
+        match imported.source {
+            Some((source, existing)) => info!(
+                "{} {} (uid {})",
+                if existing {
+                    "already imported"
+                } else {
+                    "imported"
+                },
+                imported.file,
+                source.uid
+            ),
+            None => info!("copied {}", imported.file),
+        }
+    }
+
+    let serialized = match serialize_ron(&sources) {
+        Ok(result) => result,
+        Err(e) => bail!("ASSET::IMPORT::CANNOT_SERIALIZE_ASSET_FILE", e),
+    };
+    if let Err(e) = std::fs::write(&asset_file_path, serialized) {
+        bail!("ASSET::IMPORT::CANNOT_WRITE_ASSET_FILE", e);
+    }
+    Ok(())
+}
+
 fn attach_assets(mut world: &mut World) {
     use bevy::asset::LoadState;
 
     world.resource_scope(|world, mut asset_management: Mut<AssetManagement>| {
         for mut entry in asset_management.0.iter_mut() {
-            if entry.attached.is_some() {
-                // already attached
+            if entry.attached.is_some() || entry.failed {
+                // already attached or will never be
                 continue;
             }
 
-            world.resource_scope(|world, asset_server: Mut<AssetServer>| {
-                match asset_server.get_load_state(&entry.original) {
-                    LoadState::NotLoaded => {
-                        println!("attaching simple asset {:?}", entry.source);
-                        world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
-                            asset_registry.attach_asset(entry, world);
-                        });
-                    }
-                    LoadState::Loading => { /*do nothing*/ }
-                    LoadState::Loaded => {
-                        println!("attaching asset {:?}", entry.source);
-                        world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
-                            asset_registry.attach_asset(entry, world);
-                        });
-                    }
-                    LoadState::Failed => {
-                        error!("Failed to load asset for entry {:?}", entry)
-                    }
-                    LoadState::Unloaded => { /*do nothing*/ }
+            let load_state = world
+                .resource::<AssetServer>()
+                .get_load_state(&entry.original);
+            match load_state {
+                LoadState::NotLoaded => {
+                    debug!("attaching simple asset {:?}", entry.source);
+                    world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
+                        asset_registry.attach_asset(entry, world);
+                    });
                 }
-            });
+                LoadState::Loading => { /*do nothing*/ }
+                LoadState::Loaded => {
+                    debug!("attaching asset {:?}", entry.source);
+                    world.resource_scope(|world, asset_registry: Mut<AssetRegistry>| {
+                        asset_registry.attach_asset(entry, world);
+                    });
+                }
+                LoadState::Failed => {
+                    fail_asset(world, entry, "ASSET::LOAD::FILE_FAILED".to_string());
+                }
+                LoadState::Unloaded => { /*do nothing*/ }
+            }
         }
     });
 }
@@ -1172,7 +2886,7 @@ fn get_editor_state(mut editor_state: ResMut<EditorState>) {
     gizmo_settings.enabled = false;
 }*/
 
-fn handle_attach_asset<T: Asset + Clone>(assets: &mut Assets<T>, entry: &mut AssetEntry) {
+fn handle_attach_asset<T: Asset + Clone>(assets: &mut Assets<T>, entry: &mut AssetEntry) -> bool {
     let mut clone = None;
     if let Some(asset) = assets.get(&entry.original.clone().typed()) {
         clone = Some(asset.clone());
@@ -1186,9 +2900,10 @@ fn handle_attach_asset<T: Asset + Clone>(assets: &mut Assets<T>, entry: &mut Ass
             asset.clone(),
         );
         entry.attached = Some(new_handle.clone_untyped());
-        println!("new asset attached {:?} - done processing", entry,);
+        debug!("new asset attached {:?} - done processing", entry,);
+        true
     } else {
-        error!("No asset for entry {:?}", entry)
+        false
     }
 }
 
@@ -1203,28 +2918,21 @@ fn add_simple_object(
     for event in ev_add_simple_object.iter() {
         match &event.0 {
             SimpleObject::MeshMaterial(mesh_material) => {
-                let mesh_handle = meshes.add(mesh_material.to_mesh());
-                if let HandleId::Id(_, id) = mesh_handle.id() {
-                    asset_source_list.0.push(AssetSource {
-                        source_type: AssetSourceType::AsString(mesh_material.to_string()),
-                        type_uuid: Mesh::TYPE_UUID.to_string(),
-                        uid: id,
-                    });
-                } else {
-                    error!("AssetPathId handle is not supported yet");
-                }
+                let procedural_mesh = mesh_material.default_params();
+                let mesh_source = asset_source_list.add(
+                    AssetSourceType::AsString(ron::to_string(&procedural_mesh).unwrap()),
+                    Mesh::TYPE_UUID,
+                    |handle_id| meshes.contains(&Handle::weak(handle_id)),
+                );
+                let mesh_handle = meshes.set(mesh_source.handle_id(), procedural_mesh.to_mesh());
 
                 let color = Color::rgb(0.7, 0.7, 0.7);
-                let material_handle = materials.add(color.into());
-                if let HandleId::Id(_, id) = material_handle.id() {
-                    asset_source_list.0.push(AssetSource {
-                        source_type: AssetSourceType::AsString(ron::to_string(&color).unwrap()),
-                        type_uuid: StandardMaterial::TYPE_UUID.to_string(),
-                        uid: id,
-                    });
-                } else {
-                    error!("AssetPathId handle is not supported yet");
-                }
+                let material_source = asset_source_list.add(
+                    AssetSourceType::AsString(ron::to_string(&color).unwrap()),
+                    StandardMaterial::TYPE_UUID,
+                    |handle_id| materials.contains(&Handle::weak(handle_id)),
+                );
+                let material_handle = materials.set(material_source.handle_id(), color.into());
                 let entity = commands
                     .spawn(PbrBundle {
                         mesh: mesh_handle,
@@ -1239,16 +2947,13 @@ fn add_simple_object(
             }
             SimpleObject::Light(light) => {
                 let wireframe_mesh = Mesh::from(shape::Cube { size: 1.0 });
-                let wireframe_mesh_handle = meshes.add(wireframe_mesh);
-                if let HandleId::Id(_, id) = wireframe_mesh_handle.id() {
-                    asset_source_list.0.push(AssetSource {
-                        source_type: AssetSourceType::AsString("Cube".to_string()),
-                        type_uuid: Mesh::TYPE_UUID.to_string(),
-                        uid: id,
-                    });
-                } else {
-                    error!("AssetPathId handle is not supported yet");
-                }
+                let wireframe_mesh_source = asset_source_list.add(
+                    AssetSourceType::AsString("Cube".to_string()),
+                    Mesh::TYPE_UUID,
+                    |handle_id| meshes.contains(&Handle::weak(handle_id)),
+                );
+                let wireframe_mesh_handle =
+                    meshes.set(wireframe_mesh_source.handle_id(), wireframe_mesh);
                 let name = Name::new(light.to_string());
                 match &light {
                     // WIP - spotlight has no effect - broken ???
@@ -1296,16 +3001,13 @@ fn add_simple_object(
             }
             SimpleObject::Camera(camera) => {
                 let wireframe_mesh = Mesh::from(shape::Cube { size: 1.0 });
-                let wireframe_mesh_handle = meshes.add(wireframe_mesh);
-                if let HandleId::Id(_, id) = wireframe_mesh_handle.id() {
-                    asset_source_list.0.push(AssetSource {
-                        source_type: AssetSourceType::AsString("Cube".to_string()),
-                        type_uuid: Mesh::TYPE_UUID.to_string(),
-                        uid: id,
-                    });
-                } else {
-                    error!("AssetPathId handle is not supported yet");
-                }
+                let wireframe_mesh_source = asset_source_list.add(
+                    AssetSourceType::AsString("Cube".to_string()),
+                    Mesh::TYPE_UUID,
+                    |handle_id| meshes.contains(&Handle::weak(handle_id)),
+                );
+                let wireframe_mesh_handle =
+                    meshes.set(wireframe_mesh_source.handle_id(), wireframe_mesh);
                 let name = Name::new(camera.to_string());
                 let projection = match camera {
                     Camera::Perspective => {
@@ -1341,19 +3043,246 @@ fn add_simple_object(
     }
 }
 
-fn process_scripts(world: &mut World) {
-    world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
-        world.resource_scope(|world, mut editor_state: Mut<ControlState>| {
-            if editor_state.playing {
-                registry.exec(world);
-                if editor_state.initial {
-                    editor_state.initial = false;
+fn import_gltf(
+    mut ev_import_gltf: EventReader<ImportGltf>,
+    editor_state: Res<EditorState>,
+    asset_server: Res<AssetServer>,
+    mut gltf_imports: ResMut<GltfImports>,
+) {
+    for event in ev_import_gltf.iter() {
+        if let Some(project) = &editor_state.current_project {
+            let handle = asset_server.load(project.asset_path(&event.0));
+            gltf_imports.0.push((event.0.clone(), handle));
+        }
+    }
+}
+
+fn spawn_gltf_imports(world: &mut World) {
+    use bevy::asset::LoadState;
+
+    let pending = std::mem::take(&mut world.resource_mut::<GltfImports>().0);
+    let mut still_pending = vec![];
+    for (file, handle) in pending {
+        match world.resource::<AssetServer>().get_load_state(&handle) {
+            LoadState::Loaded => {
+                if let Err(e) = spawn_gltf(world, file, &handle) {
+                    world.send_event(PushLog(e.to_string(), Level::Error));
                 }
             }
+            LoadState::Failed => {
+                world.send_event(PushLog(
+                    format!("GLTF::IMPORT::LOAD_FAILED -> {}", file),
+                    Level::Error,
+                ));
+            }
+            _ => still_pending.push((file, handle)),
+        }
+    }
+    world.resource_mut::<GltfImports>().0.extend(still_pending);
+}
+
+struct GltfImportContext {
+    file: String,
+    // sub-asset handle id -> label in the glTF file
+    labels: HashMap<HandleId, String>,
+    // sub-asset handle id -> handle with registered AssetSource uid
+    handles: HashMap<HandleId, HandleUntyped>,
+}
+
+fn spawn_gltf(world: &mut World, file: String, handle: &Handle<Gltf>) -> EResult<()> {
+    let (scene, meshes, materials) = match world.resource::<Assets<Gltf>>().get(handle) {
+        Some(gltf) => (
+            gltf.default_scene
+                .clone()
+                .or_else(|| gltf.scenes.first().cloned()),
+            gltf.meshes.clone(),
+            gltf.materials.clone(),
+        ),
+        None => bail!("GLTF::IMPORT::NOT_LOADED", file),
+    };
+    let Some(scene) = scene else {
+        bail!("GLTF::IMPORT::NO_SCENE", file);
+    };
+
+    // Sub-assets in the scene only hold handles, labels are needed to reference them as files
+    let mut labels = HashMap::new();
+    let gltf_meshes = world.resource::<Assets<GltfMesh>>();
+    for (i, mesh) in meshes.iter().enumerate() {
+        if let Some(mesh) = gltf_meshes.get(mesh) {
+            for (j, primitive) in mesh.primitives.iter().enumerate() {
+                labels.insert(primitive.mesh.id(), format!("Mesh{}/Primitive{}", i, j));
+                if let Some(material) = &primitive.material {
+                    // primitives without material use the default one
+                    labels
+                        .entry(material.id())
+                        .or_insert("MaterialDefault".to_string());
+                }
+            }
+        }
+    }
+    for (i, material) in materials.iter().enumerate() {
+        labels.insert(material.id(), format!("Material{}", i));
+    }
+
+    let mut context = GltfImportContext {
+        file,
+        labels,
+        handles: Default::default(),
+    };
+
+    world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
+        let Some(scene) = scenes.get(&scene) else {
+            bail!("GLTF::IMPORT::SCENE_NOT_LOADED", context.file);
+        };
+        let roots = scene
+            .world
+            .iter_entities()
+            .filter(|entity| !entity.contains::<Parent>())
+            .map(|entity| entity.id())
+            .collect::<Vec<_>>();
+
+        let entities = roots
+            .into_iter()
+            .map(|root| spawn_gltf_node(world, &scene.world, root, &mut context))
+            .collect::<Vec<_>>();
+        if let Some(entity) = entities.first() {
+            world.send_event(SelectEntity(*entity));
+        }
+        Ok(())
+    })
+}
+
+fn spawn_gltf_node(
+    world: &mut World,
+    scene_world: &World,
+    node: Entity,
+    context: &mut GltfImportContext,
+) -> Entity {
+    let node = scene_world.entity(node);
+    let name = node
+        .get::<Name>()
+        .cloned()
+        .unwrap_or_else(|| Name::new(context.file.clone()));
+    let transform = node.get::<Transform>().copied().unwrap_or_default();
+
+    let entity = world
+        .spawn((SpatialBundle::from_transform(transform), name))
+        .id();
+
+    if let Some(mesh) = node.get::<Handle<Mesh>>() {
+        if let Some(handle) = import_gltf_asset(world, mesh, context) {
+            world.entity_mut(entity).insert(handle);
+        }
+    }
+    if let Some(material) = node.get::<Handle<StandardMaterial>>() {
+        if let Some(handle) = import_gltf_asset(world, material, context) {
+            world.entity_mut(entity).insert(handle);
+        }
+    }
+
+    if let Some(children) = node.get::<Children>() {
+        for child in children.iter() {
+            let child = spawn_gltf_node(world, scene_world, *child, context);
+            world.entity_mut(child).set_parent(entity);
+        }
+    }
+
+    entity
+}
+
+fn import_gltf_asset<T: Asset + Clone>(
+    world: &mut World,
+    source: &Handle<T>,
+    context: &mut GltfImportContext,
+) -> Option<Handle<T>> {
+    if let Some(handle) = context.handles.get(&source.id()) {
+        return Some(handle.clone().typed());
+    }
+    let Some(label) = context.labels.get(&source.id()) else {
+        warn!("No label for glTF sub-asset {:?}, skipped", source);
+        return None;
+    };
+    let asset = world.resource::<Assets<T>>().get(source)?.clone();
+
+    let source_entry = world.resource_scope(|world, mut sources: Mut<AssetSourceList>| {
+        let assets = world.resource::<Assets<T>>();
+        sources.add(
+            AssetSourceType::AsFile(format!("{}#{}", context.file, label)),
+            T::TYPE_UUID,
+            |handle_id| assets.contains(&Handle::weak(handle_id)),
+        )
+    });
+    let handle = world
+        .resource_mut::<Assets<T>>()
+        .set(source_entry.handle_id(), asset);
+    world.resource_mut::<AssetManagement>().push(AssetEntry {
+        source: source_entry,
+        original: source.clone_untyped(),
+        attached: Some(handle.clone_untyped()),
+        failed: false,
+    });
+
+    context.handles.insert(source.id(), handle.clone_untyped());
+    Some(handle)
+}
+
+/// Systems registered by scripts run only while playing, see `ScriptSystems`.
+fn run_script_systems(world: &mut World) {
+    if world.resource::<ControlState>().playing {
+        // schedule is added on build and replaced on script loads, see `rebuild_schedule`
+        if let Err(e) = world.try_run_schedule(ScriptUpdate) {
+            error!("Cannot run script systems: {}", e);
+        }
+    }
+}
+
+/// Input and time are copied for scripts once per frame, `process_scripts` and
+/// `process_scripts_fixed` pass the same context.
+fn update_script_context(world: &mut World) {
+    let state = world.resource::<ControlState>();
+    if state.playing {
+        let play_state = state.play_state();
+        let context = ScriptContext::from_world(world, play_state);
+        world.insert_resource(context);
+    } else {
+        world.remove_resource::<ScriptContext>();
+    }
+}
+
+fn process_scripts_fixed(world: &mut World) {
+    if !world.resource::<ControlState>().playing {
+        return;
+    }
+    if !world.contains_resource::<ScriptContext>() {
+        // playing started after the context was updated this frame
+        update_script_context(world);
+    }
+    world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+        world.resource_scope(|world, context: Mut<ScriptContext>| {
+            registry.fixed_exec(world, &context);
         });
     });
 }
 
+fn process_scripts(world: &mut World) {
+    if !world.resource::<ControlState>().playing {
+        return;
+    }
+    if !world.contains_resource::<ScriptContext>() {
+        // playing started after the context was updated this frame
+        update_script_context(world);
+    }
+    world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+        world.resource_scope(|world, context: Mut<ScriptContext>| {
+            registry.exec(world, &context);
+        });
+    });
+    let mut editor_state = world.resource_mut::<ControlState>();
+    if editor_state.initial {
+        editor_state.initial = false;
+    }
+}
+
 #[derive(Event)]
 struct LoadScript(String, bool);
 
@@ -1366,11 +3295,6 @@ impl Command for AttachScript {
                 registry.reload(world, self.0);
             } else {
                 registry.load(world, self.0);
-
-                let mut editor = world.resource_mut::<EditorState>();
-                if let Some(ref mut project) = &mut editor.current_project {
-                    project.project_state.script_enabled = true;
-                }
             }
         });
     }
@@ -1382,6 +3306,42 @@ fn load_scripts(mut commands: Commands, mut events: EventReader<LoadScript>) {
     }
 }
 
+#[derive(Event)]
+struct AddScriptModule(String);
+
+#[derive(Event)]
+struct RemoveScriptModule(String);
+
+fn script_modules_on_event(
+    mut editor_state: ResMut<EditorState>,
+    mut ev_add_script_module: EventReader<AddScriptModule>,
+    mut ev_remove_script_module: EventReader<RemoveScriptModule>,
+    mut ev_push_log: EventWriter<PushLog>,
+    mut commands: Commands,
+) {
+    let Some(project) = &mut editor_state.current_project else {
+        return;
+    };
+    for event in ev_add_script_module.iter() {
+        match project.add_script_module(&event.0) {
+            Ok(()) => {
+                let path = project.script_path(&event.0);
+                commands.add(AttachScript(path.display().to_string(), false));
+            }
+            Err(e) => ev_push_log.send(PushLog(e.to_string(), Level::Error)),
+        }
+    }
+    for event in ev_remove_script_module.iter() {
+        project.remove_script_module(&event.0);
+        let path = project.script_path(&event.0).display().to_string();
+        commands.add(move |world: &mut World| {
+            world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+                registry.unload(world, &path);
+            });
+        });
+    }
+}
+
 fn show_popup_on_error(mut reader: EventReader<PushLog>, mut editor_state: ResMut<EditorState>) {
     for event in reader.iter() {
         if event.1 == Level::Error || event.1 == Level::Fatal {
@@ -1397,22 +3357,206 @@ impl Command for StartPlaying {
     fn apply(self, world: &mut World) {
         let mut state = world.resource::<ControlState>();
         if state.initial {
-            println!("will apply");
             world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
                 registry.start(world);
             });
-            println!("start done");
+        } else if !state.playing {
+            world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+                registry.resume(world);
+            });
         }
         let mut state = world.resource_mut::<ControlState>();
         state.playing = true;
-        println!("updated flag");
     }
 }
 
 fn handle_start_playing(mut commands: Commands, mut events: EventReader<StartPlaying>) {
     for _ in events.iter() {
-        println!("got event");
         commands.add(StartPlaying);
         break; // only run once
     }
 }
+
+/// Context for the hooks of pausing and stopping, `update_script_context` keeps one only while
+/// playing.
+fn hook_context(world: &mut World) -> ScriptContext {
+    let play_state = world.resource::<ControlState>().play_state();
+    ScriptContext::from_world(world, play_state)
+}
+
+impl Command for PausePlaying {
+    fn apply(self, world: &mut World) {
+        if !world.resource::<ControlState>().playing {
+            return;
+        }
+        world.resource_mut::<ControlState>().playing = false;
+        let context = hook_context(world);
+        world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+            registry.pause(world, &context);
+        });
+    }
+}
+
+impl Command for StopPlaying {
+    fn apply(self, world: &mut World) {
+        let mut state = world.resource_mut::<ControlState>();
+        state.playing = false;
+        // back to editing, the world is reset below
+        state.initial = true;
+        let context = hook_context(world);
+        world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+            registry.stop(world, &context);
+        });
+        ResetWorld.apply(world);
+    }
+}
+
+fn handle_pause_and_stop(
+    mut commands: Commands,
+    mut ev_pause: EventReader<PausePlaying>,
+    mut ev_stop: EventReader<StopPlaying>,
+) {
+    if ev_pause.iter().next().is_some() {
+        commands.add(PausePlaying);
+    }
+    if ev_stop.iter().next().is_some() {
+        commands.add(StopPlaying);
+    }
+    ev_pause.clear();
+    ev_stop.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevytor_script::PlayState;
+
+    fn file_source(file: &str, uid: u64) -> AssetSource {
+        AssetSource {
+            source_type: AssetSourceType::AsFile(file.to_string()),
+            type_uuid: Image::TYPE_UUID.to_string(),
+            uid,
+            pinned: false,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn kept_sources_include_textures_of_kept_materials() {
+        let material_data = MaterialData {
+            base_color_texture: Some("wood.png".to_string()),
+            ..default()
+        };
+        let material = AssetSource {
+            source_type: AssetSourceType::AsString(ron::to_string(&material_data).unwrap()),
+            type_uuid: StandardMaterial::TYPE_UUID.to_string(),
+            uid: 1,
+            pinned: false,
+            name: None,
+        };
+        let texture = file_source("wood.png", 2);
+        let unused = file_source("stone.png", 3);
+        let sources = vec![material.clone(), texture.clone(), unused.clone()];
+
+        let used = HashSet::from([material.handle_id()]);
+        let kept = kept_asset_sources(&sources, &used);
+        assert!(kept.contains(&material.handle_id()));
+        assert!(kept.contains(&texture.handle_id()));
+        assert!(!kept.contains(&unused.handle_id()));
+
+        assert!(kept_asset_sources(&sources, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn kept_sources_include_unused_library_materials() {
+        let material = AssetSource {
+            source_type: AssetSourceType::AsString(
+                ron::to_string(&MaterialData {
+                    base_color_texture: Some("wood.png".to_string()),
+                    ..default()
+                })
+                .unwrap(),
+            ),
+            type_uuid: StandardMaterial::TYPE_UUID.to_string(),
+            uid: 1,
+            pinned: false,
+            name: Some("Wood".to_string()),
+        };
+        let texture = file_source("wood.png", 2);
+        let sources = vec![material.clone(), texture.clone()];
+
+        let kept = kept_asset_sources(&sources, &HashSet::new());
+        assert!(kept.contains(&material.handle_id()));
+        assert!(kept.contains(&texture.handle_id()));
+    }
+
+    #[test]
+    fn raw_handle_ids_match_whole_uid() {
+        let raw = format!(
+            r#"(texture: (id: Id("{}", 12345)), count: 1234)"#,
+            Image::TYPE_UUID
+        );
+        let mut handle_ids = HashSet::new();
+        collect_raw_handle_ids(&raw, &mut handle_ids);
+
+        assert!(handle_ids.contains(&file_source("a.png", 12345).handle_id()));
+        assert!(!handle_ids.contains(&file_source("a.png", 1234).handle_id()));
+        assert!(!handle_ids.contains(&file_source("a.png", 123).handle_id()));
+    }
+
+    #[test]
+    fn remap_replaces_only_mapped_handle_ids() {
+        let (old, new, other) = (
+            file_source("a.png", 1).handle_id(),
+            file_source("a.png", 2).handle_id(),
+            file_source("b.png", 3).handle_id(),
+        );
+        let remapped = HashMap::from([(old, new)]);
+
+        let mut handle = Handle::<Image>::weak(old);
+        remap_handle_ids(&mut handle, &remapped);
+        assert_eq!(handle.id(), new);
+
+        let raw = format!(
+            r#"(a: (id: Id("{}", 1)), b: (id: Id("{}", 3)))"#,
+            Image::TYPE_UUID,
+            Image::TYPE_UUID
+        );
+        let mut handle_ids = HashSet::new();
+        collect_raw_handle_ids(&remap_raw_handle_ids(&raw, &remapped), &mut handle_ids);
+        assert_eq!(handle_ids, HashSet::from([new, other]));
+    }
+
+    #[test]
+    fn allocated_uids_skip_existing_and_live_ids() {
+        let type_uuid = Image::TYPE_UUID.to_string();
+        let first = allocate_uid(&[], &type_uuid, "a.png", |_| false);
+        let existing = file_source("a.png", first);
+        let second = allocate_uid(&[existing.clone()], &type_uuid, "a.png", |_| false);
+        assert_ne!(first, second);
+
+        let third = allocate_uid(&[existing], &type_uuid, "a.png", |handle_id| {
+            handle_id == file_source("a.png", second).handle_id()
+        });
+        assert_ne!(third, first);
+        assert_ne!(third, second);
+    }
+
+    #[test]
+    fn hooks_of_pause_and_stop_see_their_play_state() {
+        let mut world = World::new();
+        world.init_resource::<ScriptableRegistry>();
+        world.init_resource::<ControlState>();
+        let mut state = world.resource_mut::<ControlState>();
+        state.playing = true;
+        state.initial = false;
+        assert_eq!(hook_context(&mut world).play_state, PlayState::Playing);
+
+        PausePlaying.apply(&mut world);
+        assert_eq!(hook_context(&mut world).play_state, PlayState::Paused);
+
+        StartPlaying.apply(&mut world);
+        StopPlaying.apply(&mut world);
+        assert_eq!(hook_context(&mut world).play_state, PlayState::Editing);
+    }
+}
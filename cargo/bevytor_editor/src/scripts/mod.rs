@@ -1,42 +1,294 @@
+use crate::bail;
 use crate::core::events::StartPlaying;
+use crate::core::unknown_components::{resolve_unknown_components, UnknownComponents};
+use crate::error::{EResult, Error};
 use crate::logs::{Level, LogBuffer, PushLog};
 use crate::plugin::{LoadProjectProgress, LoadProjectStep};
 use bevy::ecs::system::SystemState;
 use bevy::prelude::{
-    AppTypeRegistry, EventReader, Input, KeyCode, Mut, ReflectComponent, Res, ResMut, Resource,
-    Transform, World,
+    debug, error, warn, AppTypeRegistry, Entity, EventReader, Mut, ReflectComponent, Res, ResMut,
+    Resource, Schedule, Transform, World,
 };
-use bevy::reflect::{FromType, TypeData};
+use bevy::reflect::serde::TypedReflectSerializer;
+use bevy::reflect::{FromType, TypeData, TypeRegistry};
 use bevy::tasks::{AsyncComputeTaskPool, Task};
 use bevy::utils::tracing::instrument::WithSubscriber;
-use bevy::utils::HashMap;
-use bevytor_script::{CreateScript, Script};
+use bevy::utils::{HashMap, HashSet};
+use bevytor_script::{
+    ComponentRegistry, CreateScript, GetScriptMetadata, Script, ScriptContext, ScriptMetadata,
+    ScriptSystem, ScriptSystems, ScriptUpdate,
+};
 use futures_lite::future;
 use libloading::{Library, Symbol};
 use std::any::TypeId;
-use std::ffi::OsStr;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::ffi::CStr;
+use std::fmt;
+use std::io::Read;
+use std::mem::ManuallyDrop;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub struct ScriptEntry {
     folder_path: String,
+    // systems registered by the script's `init`, dropped before the library is closed
+    systems: Vec<ScriptSystem>,
     state: LoadedScript,
+    // component types registered by the script's `init`
+    types: Vec<TypeId>,
 }
 
 pub struct LoadedScript {
-    library: Library,
-    script: Box<dyn Script>,
+    // script code lives in the library, see `Drop`
+    library: ManuallyDrop<Library>,
+    script: ManuallyDrop<Box<dyn Script>>,
     lib_path: String,
 }
 
-#[derive(Resource, Default)]
+impl Drop for LoadedScript {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.script);
+            ManuallyDrop::drop(&mut self.library);
+        }
+        // timestamped copy is not needed anymore, see `ScriptableRegistry::clone_lib_file`
+        if let Err(e) = std::fs::remove_file(&self.lib_path) {
+            warn!("Cannot remove script copy {}: {}", self.lib_path, e);
+        }
+    }
+}
+
+/// Compiler message of a script build, see `ScriptableRegistry::build`.
+#[derive(Clone)]
+pub struct BuildProblem {
+    pub level: Level,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u64>,
+    pub column: Option<u64>,
+    pub rendered: String,
+}
+
+impl BuildProblem {
+    /// Parses the `message` of a cargo `compiler-message`.
+    fn from_message(message: &serde_json::Value) -> Option<Self> {
+        let level = match message["level"].as_str()? {
+            "error" | "error: internal compiler error" => Level::Error,
+            "warning" => Level::Warn,
+            _ => Level::Info,
+        };
+        let text = message["message"].as_str()?.to_string();
+        let primary_span = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+        Some(Self {
+            level,
+            file: primary_span.and_then(|span| span["file_name"].as_str().map(String::from)),
+            line: primary_span.and_then(|span| span["line_start"].as_u64()),
+            column: primary_span.and_then(|span| span["column_start"].as_u64()),
+            rendered: message["rendered"].as_str().unwrap_or(&text).to_string(),
+            message: text,
+        })
+    }
+}
+
+impl fmt::Display for BuildProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => {
+                write!(f, "{}:{}:{} {}", file, line, column, self.message)
+            }
+            (Some(file), _, _) => write!(f, "{} {}", file, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+struct BuildOutput {
+    success: bool,
+    lib_path: Option<PathBuf>,
+    problems: Vec<BuildProblem>,
+}
+
+impl BuildOutput {
+    /// Parses the output of `cargo build --message-format=json`, the library is the artifact of
+    /// the crate at `manifest_path` (canonicalized).
+    fn from_messages(
+        success: bool,
+        stdout: &str,
+        stderr: &str,
+        manifest_path: Option<&Path>,
+    ) -> Self {
+        let mut build_output = BuildOutput {
+            success,
+            lib_path: None,
+            problems: vec![],
+        };
+        for message in stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        {
+            match message["reason"].as_str() {
+                Some("compiler-message") => build_output
+                    .problems
+                    .extend(BuildProblem::from_message(&message["message"])),
+                Some("compiler-artifact") => {
+                    let artifact_manifest_path = message["manifest_path"]
+                        .as_str()
+                        .and_then(|path| Path::new(path).canonicalize().ok());
+                    if artifact_manifest_path.is_none()
+                        || artifact_manifest_path.as_deref() != manifest_path
+                    {
+                        continue;
+                    }
+                    build_output.lib_path = message["filenames"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|filename| filename.as_str().map(PathBuf::from))
+                        .find(|filename| filename.to_string_lossy().ends_with(DLL_SUFFIX));
+                }
+                _ => {}
+            }
+        }
+
+        // ex.: invalid manifest, cargo reports it only on stderr
+        if !build_output.success && build_output.problems.is_empty() {
+            build_output.problems.push(BuildProblem {
+                level: Level::Error,
+                message: "cargo build failed".to_string(),
+                file: None,
+                line: None,
+                column: None,
+                rendered: stderr.to_string(),
+            });
+        }
+        build_output
+    }
+}
+
+/// Progress of loading a script, see `ScriptableRegistry::status`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BuildStatus {
+    #[default]
+    Queued,
+    Compiling,
+    Loading,
+    Failed,
+    Loaded,
+}
+
+impl fmt::Display for BuildStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            BuildStatus::Queued => "queued",
+            BuildStatus::Compiling => "compiling",
+            BuildStatus::Loading => "loading",
+            BuildStatus::Failed => "failed",
+            BuildStatus::Loaded => "loaded",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Shared by a load task and the registry, so the build can be followed and cancelled.
+#[derive(Default)]
+struct BuildControl {
+    status: Mutex<BuildStatus>,
+    // running cargo process
+    child: Mutex<Option<Child>>,
+    cancelled: AtomicBool,
+    // build started and not finished yet
+    running: AtomicBool,
+}
+
+impl BuildControl {
+    fn status(&self) -> BuildStatus {
+        *self.status.lock().unwrap()
+    }
+
+    fn set_status(&self, status: BuildStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Marks the build as running, `false` if it was cancelled before it started.
+    fn start(&self) -> bool {
+        let _status = self.status.lock().unwrap();
+        if self.is_cancelled() {
+            return false;
+        }
+        self.running.store(true, Ordering::SeqCst);
+        true
+    }
+
+    fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Kills the running build, the task finishes with an error.
+    fn cancel(&self) {
+        let _status = self.status.lock().unwrap();
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            let _ = child.kill();
+        }
+    }
+}
+
+struct LoadTask {
+    task: Task<LoadOutput>,
+    control: Arc<BuildControl>,
+}
+
+/// Result of a load task, build problems are reported even if the script was not loaded.
+struct LoadOutput {
+    folder_path: String,
+    problems: Vec<BuildProblem>,
+    result: EResult<LoadedScript>,
+}
+
+#[derive(Resource)]
 pub struct ScriptableRegistry {
-    // TODO rewrite in a way that one "script" - key = folder_path - has multiple versions
-    // TODO each with state compiling, loaded, old, etc.
-    compiling_impls: HashMap<String, Task<((Library, Box<dyn Script>), String, String)>>,
+    compiling_impls: HashMap<String, LoadTask>,
     impls: HashMap<String, ScriptEntry>,
-    pub old_impls: Vec<LoadedScript>,
+    // replaced versions, kept until nothing uses their library, see `release_old_impls`
+    old_impls: Vec<ScriptEntry>,
+    // library of the version that was loaded when a script type got its component id - the world
+    // keeps the drop function and type name of the first registration (components cannot be
+    // unregistered), so that library is never closed
+    component_owners: HashMap<TypeId, String>,
+    // problems of the last build, by folder
+    build_problems: HashMap<String, Vec<BuildProblem>>,
+    // status of the last finished load, by folder
+    statuses: HashMap<String, BuildStatus>,
+    // cargo is killed if a build takes longer
+    pub build_timeout: Duration,
+}
+
+impl Default for ScriptableRegistry {
+    fn default() -> Self {
+        Self {
+            compiling_impls: HashMap::new(),
+            impls: HashMap::new(),
+            old_impls: vec![],
+            component_owners: HashMap::new(),
+            build_problems: HashMap::new(),
+            statuses: HashMap::new(),
+            build_timeout: DEFAULT_BUILD_TIMEOUT,
+        }
+    }
 }
 
 impl ScriptableRegistry {
@@ -49,61 +301,246 @@ impl ScriptableRegistry {
     }
 
     fn load_async(&mut self, world: &mut World, folder_path: String, force: bool) {
+        // a newer request replaces the build in progress, it is queued until the old one stops
+        let previous = self.compiling_impls.remove(&folder_path).map(|old_task| {
+            old_task.control.cancel();
+            old_task.control
+        });
+
         let folder_path_clone = folder_path.clone();
+        let loaded_lib_paths = self.loaded_lib_paths();
+        let control = Arc::new(BuildControl::default());
+        let task_control = control.clone();
+        let timeout = self.build_timeout;
         let pool = AsyncComputeTaskPool::get();
         let task = pool.spawn(async move {
-            let base_path = Path::new(folder_path_clone.as_str());
-            if force || !Self::check_exists(base_path) {
-                Self::build(base_path);
+            if let Some(previous) = previous {
+                // status stays `Queued` while waiting
+                while previous.is_running() {
+                    std::thread::sleep(BUILD_POLL_INTERVAL);
+                }
             }
-            let clone_lib_path = Self::clone_lib_file(base_path);
-
-            println!("load async {}", clone_lib_path.to_str().unwrap());
-            unsafe {
-                (
-                    ScriptableRegistry::load_script(&clone_lib_path),
-                    clone_lib_path.to_str().unwrap().to_string(),
-                    folder_path_clone,
-                )
+            let base_path = Path::new(folder_path_clone.as_str());
+            let mut problems = vec![];
+            let result = Self::build_and_load(
+                base_path,
+                force,
+                &loaded_lib_paths,
+                &mut problems,
+                &task_control,
+                timeout,
+            );
+            task_control.finish();
+            LoadOutput {
+                folder_path: folder_path_clone,
+                problems,
+                result,
             }
         });
 
-        let old_entry = self.compiling_impls.insert(folder_path, task);
-        if let Some(_) = old_entry {
-            // TODO halt task?
-        }
+        self.compiling_impls
+            .insert(folder_path, LoadTask { task, control });
         world.send_event(PushLog("Script loading started".to_string(), Level::Info));
     }
 
-    fn clone_lib_file(base_path: &Path) -> PathBuf {
-        let lib_path = base_path.join("target/debug/scripts.dll");
+    fn build_and_load(
+        base_path: &Path,
+        force: bool,
+        loaded_lib_paths: &[PathBuf],
+        problems: &mut Vec<BuildProblem>,
+        control: &BuildControl,
+        timeout: Duration,
+    ) -> EResult<LoadedScript> {
+        if !control.start() {
+            bail!("SCRIPT::BUILD::CANCELLED", base_path.display());
+        }
+        let mut lib_path = None;
+        if force || !Self::check_exists(base_path) {
+            control.set_status(BuildStatus::Compiling);
+            let output = Self::build(base_path, control, timeout)?;
+            *problems = output.problems;
+            if !output.success {
+                bail!("SCRIPT::BUILD::FAILED", base_path.display());
+            }
+            lib_path = output.lib_path;
+        }
+        if control.is_cancelled() {
+            bail!("SCRIPT::BUILD::CANCELLED", base_path.display());
+        }
+        control.set_status(BuildStatus::Loading);
+        let lib_path = lib_path.unwrap_or_else(|| Self::default_lib_path(base_path));
+        Self::remove_stale_copies(&lib_path, loaded_lib_paths);
+        let clone_lib_path = Self::clone_lib_file(&lib_path)?;
 
+        debug!("load async {}", clone_lib_path.display());
+        unsafe { Self::load_script(&clone_lib_path) }
+    }
+
+    /// Library is loaded from a timestamped copy, so the original can be rebuilt while loaded.
+    fn clone_lib_file(lib_path: &Path) -> EResult<PathBuf> {
         let start = SystemTime::now();
         let since_the_epoch = start
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards");
 
-        let clone_lib_path = base_path.join(format!(
-            "target/debug/scripts-{}.dll",
-            since_the_epoch.as_millis()
+        let clone_lib_path = lib_path.with_file_name(format!(
+            "{}-{}{}",
+            Self::lib_stem(lib_path),
+            since_the_epoch.as_millis(),
+            DLL_SUFFIX
         ));
-        std::fs::copy(lib_path, &clone_lib_path).expect("Cannot copy lib file");
-        clone_lib_path
+        if let Err(e) = std::fs::copy(lib_path, &clone_lib_path) {
+            bail!("SCRIPT::LOAD::CANNOT_COPY_LIBRARY", e);
+        }
+        Ok(clone_lib_path)
+    }
+
+    /// Removes copies left by previous runs of the editor, copies in use are kept.
+    fn remove_stale_copies(lib_path: &Path, loaded_lib_paths: &[PathBuf]) {
+        let Some(folder) = lib_path.parent() else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            return;
+        };
+        let prefix = format!("{}-", Self::lib_stem(lib_path));
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let is_copy = file_name.starts_with(&prefix) && file_name.ends_with(DLL_SUFFIX);
+            if is_copy && !loaded_lib_paths.contains(&path) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!("Cannot remove stale script copy {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    fn lib_stem(lib_path: &Path) -> String {
+        let file_name = lib_path
+            .file_name()
+            .map_or(String::new(), |name| name.to_string_lossy().to_string());
+        file_name
+            .strip_suffix(DLL_SUFFIX)
+            .unwrap_or(&file_name)
+            .to_string()
+    }
+
+    fn loaded_lib_paths(&self) -> Vec<PathBuf> {
+        self.impls
+            .values()
+            .map(|entry| &entry.state)
+            .chain(self.old_impls.iter().map(|entry| &entry.state))
+            .map(|state| PathBuf::from(&state.lib_path))
+            .collect()
+    }
+
+    /// Unloads the script of the folder. Its components are removed from entities and kept as raw
+    /// data (see `UnknownComponents`), its library is closed unless it owns a component type.
+    pub fn unload(&mut self, world: &mut World, folder_path: &str) {
+        if let Some(task) = self.compiling_impls.remove(folder_path) {
+            task.control.cancel();
+        }
+        self.build_problems.remove(folder_path);
+        self.statuses.remove(folder_path);
+        let Some(entry) = self.impls.remove(folder_path) else {
+            return;
+        };
+        entry.state.script.on_unload(world);
+        let snapshots = snapshot_components(world, &entry.types);
+        replace_components(world, snapshots);
+        unregister_types(world, &entry.types);
+        self.rebuild_schedule(world);
+        // the world keeps the component ids registered by the library, see `component_owners`
+        record_component_owners(&mut self.component_owners, world, &entry);
+        self.old_impls.push(entry);
+        self.release_old_impls();
+    }
+
+    /// Closes libraries of replaced script versions, once their systems are out of the schedule.
+    /// Versions that own a component type stay loaded, see `component_owners`.
+    fn release_old_impls(&mut self) {
+        let owners = self.component_owners.values().collect::<HashSet<_>>();
+        // closes the library and removes its copy, see `LoadedScript::drop`
+        self.old_impls
+            .retain(|entry| owners.contains(&entry.state.lib_path));
+    }
+
+    /// Systems cannot be removed from a schedule, so it is rebuilt from the loaded scripts.
+    fn rebuild_schedule(&self, world: &mut World) {
+        let mut schedule = Schedule::new();
+        for entry in self.impls.values() {
+            for system in entry.systems.iter() {
+                system.add_to(&mut schedule);
+            }
+        }
+        world.add_schedule(schedule, ScriptUpdate);
+    }
+
+    /// Names of the systems registered by the script of the folder.
+    pub fn system_names(&self, folder_path: &str) -> Vec<&str> {
+        self.impls.get(folder_path).map_or(vec![], |entry| {
+            entry
+                .systems
+                .iter()
+                .map(|system| system.name.as_str())
+                .collect()
+        })
+    }
+
+    pub fn is_loaded(&self, folder_path: &str) -> bool {
+        self.impls.contains_key(folder_path)
+    }
+
+    pub fn is_compiling(&self, folder_path: &str) -> bool {
+        self.compiling_impls.contains_key(folder_path)
+    }
+
+    /// Status of the load in progress, or of the last one.
+    pub fn status(&self, folder_path: &str) -> Option<BuildStatus> {
+        match self.compiling_impls.get(folder_path) {
+            Some(task) => Some(task.control.status()),
+            None => self.statuses.get(folder_path).copied(),
+        }
+    }
+
+    pub fn build_problems(&self) -> impl Iterator<Item = &BuildProblem> {
+        self.build_problems.values().flatten()
     }
 
     pub fn start(&mut self, world: &mut World) {
         for (_, entry) in &mut self.impls {
-            println!("run start");
             entry.state.script.start(world);
-            println!("done start");
         }
     }
 
-    pub fn exec(&mut self, world: &mut World) {
+    pub fn exec(&mut self, world: &mut World, context: &ScriptContext) {
         for (_, entry) in &mut self.impls {
-            world.resource_scope(|world, input: Mut<Input<KeyCode>>| {
-                entry.state.script.run(world, &input);
-            });
+            entry.state.script.run(world, context);
+        }
+    }
+
+    pub fn fixed_exec(&mut self, world: &mut World, context: &ScriptContext) {
+        for (_, entry) in &mut self.impls {
+            entry.state.script.fixed_run(world, context);
+        }
+    }
+
+    pub fn pause(&mut self, world: &mut World, context: &ScriptContext) {
+        for (_, entry) in &mut self.impls {
+            entry.state.script.on_pause(world, context);
+        }
+    }
+
+    pub fn resume(&mut self, world: &mut World) {
+        for (_, entry) in &mut self.impls {
+            entry.state.script.on_resume(world);
+        }
+    }
+
+    pub fn stop(&mut self, world: &mut World, context: &ScriptContext) {
+        for (_, entry) in &mut self.impls {
+            entry.state.script.on_stop(world, context);
         }
     }
 
@@ -112,52 +549,189 @@ impl ScriptableRegistry {
     }
 
     fn check_exists(base_path: &Path) -> bool {
-        let lib_path = base_path.join("target/debug/scripts.dll");
-        lib_path.exists()
+        Self::default_lib_path(base_path).exists()
+    }
+
+    /// Where cargo puts the library of the crate (named after its folder), used when it is not
+    /// built by the editor.
+    fn default_lib_path(base_path: &Path) -> PathBuf {
+        let crate_name = base_path.file_name().map_or("scripts".to_string(), |name| {
+            name.to_string_lossy().replace('-', "_")
+        });
+        base_path
+            .join("target")
+            .join("debug")
+            .join(format!("{}{}{}", DLL_PREFIX, crate_name, DLL_SUFFIX))
     }
 
-    fn build(base_path: &Path) {
-        let output = Command::new("cargo")
+    /// Builds the crate, the library is found in cargo's build artifact messages and compiler
+    /// messages are returned as problems. Cargo is killed when the build is cancelled or times out.
+    fn build(base_path: &Path, control: &BuildControl, timeout: Duration) -> EResult<BuildOutput> {
+        let mut child = match Command::new("cargo")
             .arg("build")
+            .arg("--message-format=json")
             .current_dir(base_path)
-            .output()
-            .expect("failed to build script");
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(result) => result,
+            Err(e) => bail!("SCRIPT::BUILD::CANNOT_RUN_CARGO", e),
+        };
+        // pipes are read while waiting, cargo would block on a full pipe
+        let stdout_reader = read_pipe(child.stdout.take());
+        let stderr_reader = read_pipe(child.stderr.take());
+        *control.child.lock().unwrap() = Some(child);
 
-        println!(
-            "DONE {}",
-            output.status //String::from_utf8(output.stdout).expect("failed to parse command output")
-        )
+        let started = Instant::now();
+        let status = loop {
+            if control.is_cancelled() {
+                Self::stop_build(control);
+                bail!("SCRIPT::BUILD::CANCELLED", base_path.display());
+            }
+            if started.elapsed() > timeout {
+                Self::stop_build(control);
+                bail!(
+                    "SCRIPT::BUILD::TIMEOUT",
+                    format!("{} after {}s", base_path.display(), timeout.as_secs())
+                );
+            }
+            let exit_status = match control.child.lock().unwrap().as_mut() {
+                Some(child) => child.try_wait(),
+                None => bail!("SCRIPT::BUILD::CANCELLED", base_path.display()),
+            };
+            match exit_status {
+                Ok(Some(status)) => break status,
+                Ok(None) => std::thread::sleep(BUILD_POLL_INTERVAL),
+                Err(e) => {
+                    Self::stop_build(control);
+                    bail!("SCRIPT::BUILD::CANNOT_RUN_CARGO", e);
+                }
+            }
+        };
+        control.child.lock().unwrap().take();
+        // killed by `cancel` between the checks
+        if control.is_cancelled() {
+            bail!("SCRIPT::BUILD::CANCELLED", base_path.display());
+        }
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+
+        let manifest_path = base_path.join("Cargo.toml").canonicalize().ok();
+        Ok(BuildOutput::from_messages(
+            status.success(),
+            &String::from_utf8_lossy(&stdout),
+            &String::from_utf8_lossy(&stderr),
+            manifest_path.as_deref(),
+        ))
     }
 
-    unsafe fn load_script<P: AsRef<OsStr>>(path: P) -> (Library, Box<dyn Script>) {
-        let lib = Library::new(path).unwrap();
-        let func: Symbol<CreateScript> = lib.get(b"_create_script").unwrap();
-        let res = func();
-        let script = Box::from_raw(res);
-        (lib, script)
+    fn stop_build(control: &BuildControl) {
+        if let Some(mut child) = control.child.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Rejects libraries built with other versions than the editor, see `ScriptMetadata`.
+    fn check_metadata(raw_metadata: &str, path: &Path) -> EResult<()> {
+        let Some(metadata) = ScriptMetadata::from_raw(raw_metadata) else {
+            bail!("SCRIPT::LOAD::INVALID_METADATA", raw_metadata);
+        };
+        if let Some(mismatch) = metadata.mismatch(&ScriptMetadata::current()) {
+            bail!(
+                "SCRIPT::LOAD::INCOMPATIBLE",
+                format!(
+                    "{} (script != editor), rebuild the script: {}",
+                    mismatch,
+                    path.display()
+                )
+            );
+        }
+        Ok(())
+    }
+
+    unsafe fn load_script(path: &Path) -> EResult<LoadedScript> {
+        let lib = match Library::new(path) {
+            Ok(result) => result,
+            Err(e) => bail!("SCRIPT::LOAD::CANNOT_OPEN_LIBRARY", e),
+        };
+        // library is compatible only if built with the same compiler and shared crates
+        let raw_metadata = {
+            let func: Symbol<GetScriptMetadata> = match lib.get(b"_script_metadata") {
+                Ok(result) => result,
+                Err(e) => bail!("SCRIPT::LOAD::NO_METADATA", e),
+            };
+            CStr::from_ptr(func()).to_string_lossy().to_string()
+        };
+        Self::check_metadata(&raw_metadata, path)?;
+
+        let script = {
+            let func: Symbol<CreateScript> = match lib.get(b"_create_script") {
+                Ok(result) => result,
+                Err(e) => bail!("SCRIPT::LOAD::NO_CREATE_SCRIPT", e),
+            };
+            Box::from_raw(func())
+        };
+        Ok(LoadedScript {
+            library: ManuallyDrop::new(lib),
+            script: ManuallyDrop::new(script),
+            lib_path: path.display().to_string(),
+        })
     }
 }
 
 pub fn handle_tasks(world: &mut World) {
     world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
-        let mut new_impls = vec![];
-        for (_, task) in registry.compiling_impls.iter_mut() {
-            if let Some(((library, script), lib_path, folder_path)) =
-                future::block_on(future::poll_once(&mut *task))
-            {
-                // Task is complete, init and update
-                new_impls.push(ScriptEntry {
-                    folder_path,
-                    state: LoadedScript {
-                        library,
-                        script,
-                        lib_path,
-                    },
-                });
-            }
-        }
-        for new_impl in new_impls {
-            registry.compiling_impls.remove(&new_impl.folder_path);
+        let mut outputs = vec![];
+        for (_, load_task) in registry.compiling_impls.iter_mut() {
+            if let Some(output) = future::block_on(future::poll_once(&mut load_task.task)) {
+                outputs.push(output);
+            }
+        }
+        for output in outputs {
+            // Task is complete, init and update
+            registry.compiling_impls.remove(&output.folder_path);
+            let mut logger = world.resource_mut::<LogBuffer>();
+            for problem in output.problems.iter() {
+                logger.write(problem.to_string(), problem.level);
+            }
+            registry
+                .build_problems
+                .insert(output.folder_path.clone(), output.problems);
+
+            let state = match output.result {
+                Ok(result) => result,
+                Err(e) => {
+                    // previous version of the script (if any) stays loaded
+                    registry
+                        .statuses
+                        .insert(output.folder_path, BuildStatus::Failed);
+                    world.send_event(PushLog(e.to_string(), Level::Error));
+                    continue;
+                }
+            };
+            registry
+                .statuses
+                .insert(output.folder_path.clone(), BuildStatus::Loaded);
+            let mut new_impl = ScriptEntry {
+                folder_path: output.folder_path,
+                systems: vec![],
+                state,
+                types: vec![],
+            };
+            // components of the outgoing script are serialized with its registrations, before
+            // the new script overwrites them
+            let registry = &mut *registry;
+            let snapshots = match registry.impls.get(&new_impl.folder_path) {
+                Some(old_impl) => {
+                    old_impl.state.script.on_before_reload(world);
+                    // component ids created until now are from the outgoing library
+                    record_component_owners(&mut registry.component_owners, world, old_impl);
+                    snapshot_components(world, &old_impl.types)
+                }
+                None => vec![],
+            };
             {
                 let new_types = new_impl.state.script.init(world);
                 let type_registry = world.resource_mut::<AppTypeRegistry>();
@@ -167,19 +741,45 @@ pub fn handle_tasks(world: &mut World) {
                     new_type.insert(component);
                     new_type.insert(serialize);
                     new_type.insert(deserialize);
+                    new_impl.types.push(new_type.type_id());
                     r.add_registration(new_type);
                 }
             }
+            if let Some(mut script_systems) = world.get_resource_mut::<ScriptSystems>() {
+                new_impl.systems = script_systems.take();
+            }
+            replace_components(world, snapshots);
+            // scene might contain components of the script, that were not known when loaded,
+            // replaced components are deserialized as the new types the same way
+            resolve_unknown_components(world);
 
             let old_impl = registry
                 .impls
                 .insert(new_impl.folder_path.clone(), new_impl);
             if let Some(old_impl) = old_impl {
-                registry.old_impls.push(old_impl.state);
+                registry.impls[&old_impl.folder_path]
+                    .state
+                    .script
+                    .on_after_reload(world);
+                // types the new version does not register anymore would point to the old library
+                let new_types = &registry.impls[&old_impl.folder_path].types;
+                let stale_types = old_impl
+                    .types
+                    .iter()
+                    .filter(|type_id| !new_types.contains(type_id))
+                    .copied()
+                    .collect::<Vec<_>>();
+                unregister_types(world, &stale_types);
+                registry.old_impls.push(old_impl);
             }
+            registry.rebuild_schedule(world);
+            registry.release_old_impls();
             let mut logger = world.resource_mut::<LogBuffer>();
             logger.write_info("Script loading complete".to_string());
+        }
 
+        // project load waits for all of its script crates
+        if registry.compiling_impls.is_empty() {
             let mut load_project_progress = world.resource_mut::<LoadProjectProgress>();
             if let LoadProjectStep::Scripts(false) = load_project_progress.0 {
                 load_project_progress.0 = LoadProjectStep::Scripts(true);
@@ -187,3 +787,391 @@ pub fn handle_tasks(world: &mut World) {
         }
     });
 }
+
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut content = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut content);
+        }
+        content
+    })
+}
+
+/// Serialized component of an outgoing script, with the old registration to remove it.
+struct ComponentSnapshot {
+    entity: Entity,
+    type_name: String,
+    raw: String,
+    reflect_component: ReflectComponent,
+}
+
+/// Components that cannot be serialized are not snapshotted, they stay on their entities.
+fn snapshot_components(world: &mut World, types: &[TypeId]) -> Vec<ComponentSnapshot> {
+    let type_registry_arc = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry_arc.read();
+    let mut snapshots = vec![];
+    let mut errors = vec![];
+    for type_id in types {
+        let Some(registration) = type_registry.get(*type_id) else {
+            continue;
+        };
+        let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+            continue;
+        };
+        for entity in world.iter_entities() {
+            let Some(component) = reflect_component.reflect(entity) else {
+                continue;
+            };
+            let serializer = TypedReflectSerializer::new(component, &type_registry);
+            match ron::to_string(&serializer) {
+                Ok(raw) => snapshots.push(ComponentSnapshot {
+                    entity: entity.id(),
+                    type_name: registration.type_name().to_string(),
+                    raw,
+                    reflect_component: reflect_component.clone(),
+                }),
+                Err(e) => errors.push(format!(
+                    "SCRIPT::SNAPSHOT::CANNOT_SERIALIZE -> {} of {:?} is kept as is: {}",
+                    registration.type_name(),
+                    entity.id(),
+                    e
+                )),
+            }
+        }
+    }
+    for error in errors {
+        error!("{}", error);
+        world.send_event(PushLog(error, Level::Error));
+    }
+    snapshots
+}
+
+/// Removes old instances and keeps them as unknown components, until they can be deserialized
+/// with the new registrations, see `resolve_unknown_components`.
+fn replace_components(world: &mut World, snapshots: Vec<ComponentSnapshot>) {
+    for snapshot in snapshots {
+        let Some(mut entity_mut) = world.get_entity_mut(snapshot.entity) else {
+            continue;
+        };
+        snapshot.reflect_component.remove(&mut entity_mut);
+        let mut unknown = entity_mut.take::<UnknownComponents>().unwrap_or_default();
+        unknown.0.push((snapshot.type_name, snapshot.raw));
+        entity_mut.insert(unknown);
+    }
+}
+
+/// Records the library of the entry as owner of its types that got a component id, unless they
+/// already have one, see `ScriptableRegistry::component_owners`.
+fn record_component_owners(
+    owners: &mut HashMap<TypeId, String>,
+    world: &World,
+    entry: &ScriptEntry,
+) {
+    for type_id in entry.types.iter() {
+        if world.components().get_id(*type_id).is_some() {
+            owners
+                .entry(*type_id)
+                .or_insert_with(|| entry.state.lib_path.clone());
+        }
+    }
+}
+
+/// Removes types from the `AppTypeRegistry` and `ComponentRegistry`, ex.: before their library
+/// is closed.
+fn unregister_types(world: &mut World, types: &[TypeId]) {
+    if types.is_empty() {
+        return;
+    }
+    world
+        .resource_mut::<ComponentRegistry>()
+        .reg
+        .retain(|type_id, _| !types.contains(type_id));
+
+    let type_registry_arc = world.resource::<AppTypeRegistry>().clone();
+    let mut type_registry = type_registry_arc.write();
+    // type registry cannot remove registrations, the kept ones are moved to a new registry
+    let mut kept = TypeRegistry::empty();
+    for registration in type_registry.iter() {
+        if !types.contains(&registration.type_id()) {
+            kept.add_registration(registration.clone());
+        }
+    }
+    *type_registry = kept;
+}
+
+const DEFAULT_BUILD_TIMEOUT: Duration = Duration::from_secs(300);
+const BUILD_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const WATCH_SCAN_INTERVAL: Duration = Duration::from_millis(500);
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(1000);
+
+/// Rebuilds scripts when their sources change, see `watch_scripts`.
+#[derive(Resource)]
+pub struct ScriptWatcher {
+    pub enabled: bool,
+    // folder -> latest modification of its sources
+    modified: HashMap<String, SystemTime>,
+    // folder -> when the last change was seen, rebuilt once changes settle
+    changed: HashMap<String, Instant>,
+    last_scan: Option<Instant>,
+}
+
+impl Default for ScriptWatcher {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            modified: Default::default(),
+            changed: Default::default(),
+            last_scan: None,
+        }
+    }
+}
+
+/// Watches `src/` and `Cargo.toml` of every script crate, that was built or loaded, and reloads
+/// it in the background once its sources stop changing.
+pub fn watch_scripts(world: &mut World) {
+    world.resource_scope(|world, mut watcher: Mut<ScriptWatcher>| {
+        if !watcher.enabled {
+            watcher.changed.clear();
+            return;
+        }
+        let now = Instant::now();
+        if watcher
+            .last_scan
+            .map_or(false, |last_scan| now - last_scan < WATCH_SCAN_INTERVAL)
+        {
+            return;
+        }
+        watcher.last_scan = Some(now);
+
+        world.resource_scope(|world, mut registry: Mut<ScriptableRegistry>| {
+            let folder_paths = registry
+                .impls
+                .keys()
+                .chain(registry.build_problems.keys())
+                .cloned()
+                .collect::<HashSet<_>>();
+            watcher
+                .modified
+                .retain(|folder_path, _| folder_paths.contains(folder_path));
+            for folder_path in folder_paths {
+                let Some(modified) = latest_source_change(Path::new(&folder_path)) else {
+                    continue;
+                };
+                let previous = watcher.modified.insert(folder_path.clone(), modified);
+                if previous.map_or(false, |previous| previous != modified) {
+                    watcher.changed.insert(folder_path, now);
+                }
+            }
+
+            let settled = watcher
+                .changed
+                .iter()
+                .filter(|(_, changed_at)| now - **changed_at >= WATCH_DEBOUNCE)
+                .map(|(folder_path, _)| folder_path.clone())
+                .collect::<Vec<_>>();
+            for folder_path in settled {
+                watcher.changed.remove(&folder_path);
+                world.send_event(PushLog(
+                    format!("Sources of {} changed, rebuilding", folder_path),
+                    Level::Info,
+                ));
+                registry.reload(world, folder_path);
+            }
+        });
+    });
+}
+
+fn latest_source_change(base_path: &Path) -> Option<SystemTime> {
+    let manifest_modified = std::fs::metadata(base_path.join("Cargo.toml"))
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let sources_modified = latest_change_in(&base_path.join("src"));
+    manifest_modified.max(sources_modified)
+}
+
+fn latest_change_in(folder: &Path) -> Option<SystemTime> {
+    let mut latest = None;
+    for entry in std::fs::read_dir(folder).ok()?.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        // folder itself changes when a file is removed
+        latest = latest.max(metadata.modified().ok());
+        if metadata.is_dir() {
+            latest = latest.max(latest_change_in(&entry.path()));
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::Component;
+    use bevytor_script::Definition;
+
+    struct EmptyScript;
+
+    impl Script for EmptyScript {
+        fn name(&self) -> &'static str {
+            "empty"
+        }
+
+        fn start(&self, _: &mut World) {}
+
+        fn run(&self, _: &mut World, _: &ScriptContext) {}
+
+        fn init(&self, _: &mut World) -> Vec<Definition> {
+            vec![]
+        }
+    }
+
+    #[derive(Component)]
+    struct Registered;
+
+    #[derive(Component)]
+    struct NeverRegistered;
+
+    /// Entry backed by the test executable, its "copy" is a file that is removed when the entry
+    /// is closed.
+    fn script_entry(folder_path: &str, version: u32, types: Vec<TypeId>) -> (ScriptEntry, PathBuf) {
+        let lib_path = std::env::temp_dir().join(format!(
+            "bevytor_test_{}_{}_{}",
+            std::process::id(),
+            folder_path,
+            version
+        ));
+        std::fs::write(&lib_path, "").unwrap();
+        #[cfg(unix)]
+        let library = libloading::os::unix::Library::this().into();
+        #[cfg(windows)]
+        let library = libloading::os::windows::Library::this().unwrap().into();
+        let entry = ScriptEntry {
+            folder_path: folder_path.to_string(),
+            systems: vec![],
+            state: LoadedScript {
+                library: ManuallyDrop::new(library),
+                script: ManuallyDrop::new(Box::new(EmptyScript)),
+                lib_path: lib_path.display().to_string(),
+            },
+            types,
+        };
+        (entry, lib_path)
+    }
+
+    #[test]
+    fn unloaded_script_is_kept_while_world_has_its_component_ids() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<ComponentRegistry>();
+        world.init_component::<Registered>();
+        let mut registry = ScriptableRegistry::default();
+
+        let (owner, owner_copy) = script_entry("owner", 1, vec![TypeId::of::<Registered>()]);
+        let (unused, unused_copy) =
+            script_entry("unused", 1, vec![TypeId::of::<NeverRegistered>()]);
+        registry.register(owner);
+        registry.register(unused);
+        registry.unload(&mut world, "owner");
+        registry.unload(&mut world, "unused");
+        assert!(owner_copy.exists());
+        assert!(!unused_copy.exists());
+
+        // the world keeps the drop function of the first version only
+        let (reloaded, reloaded_copy) = script_entry("owner", 2, vec![TypeId::of::<Registered>()]);
+        registry.register(reloaded);
+        registry.unload(&mut world, "owner");
+        assert!(owner_copy.exists());
+        assert!(!reloaded_copy.exists());
+        assert_eq!(registry.old_impls.len(), 1);
+    }
+
+    // captured from `cargo build --message-format=json` of a script crate with an unused variable,
+    // paths are replaced when the test runs
+    const WARNING_BUILD: &str = r#"{"reason":"compiler-artifact","package_id":"serde 1.0.188 (registry+https://github.com/rust-lang/crates.io-index)","manifest_path":"DEPENDENCY_MANIFEST","target":{"kind":["lib"],"crate_types":["lib"],"name":"serde","src_path":"serde-1.0.188/src/lib.rs","edition":"2018","doctest":true,"test":true},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":false},"features":["default","std"],"filenames":["target/debug/deps/libserde-2d1c0bd1f5d5b6a8.rlib"],"executable":null,"fresh":true}
+{"reason":"compiler-message","package_id":"scripts 0.1.0 (path+file:///project/scripts)","manifest_path":"SCRIPT_MANIFEST","target":{"kind":["dylib"],"crate_types":["dylib"],"name":"scripts","src_path":"src/lib.rs","edition":"2021","doctest":false,"test":true},"message":{"rendered":"warning: unused variable: `speed`\n --> src/lib.rs:12:9\n   |\n12 |     let speed = 2.0;\n   |         ^^^^^ help: if this is intentional, prefix it with an underscore: `_speed`\n\n","children":[],"code":{"code":"unused_variables","explanation":null},"level":"warning","message":"unused variable: `speed`","spans":[{"byte_end":250,"byte_start":245,"column_end":14,"column_start":9,"expansion":null,"file_name":"src/lib.rs","is_primary":true,"label":null,"line_end":12,"line_start":12,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}
+{"reason":"compiler-artifact","package_id":"scripts 0.1.0 (path+file:///project/scripts)","manifest_path":"SCRIPT_MANIFEST","target":{"kind":["dylib"],"crate_types":["dylib"],"name":"scripts","src_path":"src/lib.rs","edition":"2021","doctest":false,"test":true},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":false},"features":[],"filenames":["target/debug/scripts.d","SCRIPT_LIBRARY"],"executable":null,"fresh":false}
+{"reason":"build-finished","success":true}
+"#;
+
+    fn json_string(path: &Path) -> String {
+        let quoted = serde_json::to_string(&path.to_string_lossy()).unwrap();
+        quoted[1..quoted.len() - 1].to_string()
+    }
+
+    #[test]
+    fn script_built_with_other_versions_is_rejected() {
+        let path = Path::new("scripts.dll");
+        assert!(ScriptableRegistry::check_metadata(&ScriptMetadata::current().raw(), path).is_ok());
+
+        let other_bevy = ScriptMetadata {
+            bevy_version: "0.10.1".to_string(),
+            ..ScriptMetadata::current()
+        };
+        let error = ScriptableRegistry::check_metadata(&other_bevy.raw(), path).unwrap_err();
+        assert_eq!(error.code, "SCRIPT::LOAD::INCOMPATIBLE");
+        assert!(error.details.unwrap().starts_with("bevy 0.10.1 != "));
+
+        let other_rustc = ScriptMetadata {
+            rustc_version: "rustc 1.0.0".to_string(),
+            ..ScriptMetadata::current()
+        };
+        let error = ScriptableRegistry::check_metadata(&other_rustc.raw(), path).unwrap_err();
+        assert_eq!(error.code, "SCRIPT::LOAD::INCOMPATIBLE");
+        assert!(error.details.unwrap().starts_with("rustc rustc 1.0.0 != "));
+
+        let error = ScriptableRegistry::check_metadata("rustc=1.0.0", path).unwrap_err();
+        assert_eq!(error.code, "SCRIPT::LOAD::INVALID_METADATA");
+    }
+
+    #[test]
+    fn build_output_of_warning_only_build() {
+        // canonicalized by the parser, so they must exist
+        let script_manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let dependency_manifest = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("bevytor_core")
+            .join("Cargo.toml");
+        let library = Path::new("target")
+            .join("debug")
+            .join(format!("{}scripts{}", DLL_PREFIX, DLL_SUFFIX));
+        let stdout = WARNING_BUILD
+            .replace("DEPENDENCY_MANIFEST", &json_string(&dependency_manifest))
+            .replace("SCRIPT_MANIFEST", &json_string(&script_manifest))
+            .replace("SCRIPT_LIBRARY", &json_string(&library));
+        // cargo's progress is not json
+        let stdout = format!("   Compiling scripts v0.1.0\n{}", stdout);
+
+        let build_output = BuildOutput::from_messages(
+            true,
+            &stdout,
+            "",
+            script_manifest.canonicalize().ok().as_deref(),
+        );
+
+        assert!(build_output.success);
+        assert_eq!(build_output.lib_path, Some(library));
+        assert_eq!(build_output.problems.len(), 1);
+        let problem = &build_output.problems[0];
+        assert!(problem.level == Level::Warn);
+        assert_eq!(problem.message, "unused variable: `speed`");
+        assert_eq!(problem.file.as_deref(), Some("src/lib.rs"));
+        assert_eq!((problem.line, problem.column), (Some(12), Some(9)));
+        assert!(problem.rendered.starts_with("warning: unused variable"));
+    }
+
+    #[test]
+    fn build_output_of_failed_build_without_messages() {
+        let stderr = "error: failed to parse manifest at `scripts/Cargo.toml`";
+        let build_output =
+            BuildOutput::from_messages(false, "", stderr, Some(Path::new("Cargo.toml")));
+
+        assert!(!build_output.success);
+        assert_eq!(build_output.lib_path, None);
+        assert_eq!(build_output.problems.len(), 1);
+        assert!(build_output.problems[0].level == Level::Error);
+        assert_eq!(build_output.problems[0].rendered, stderr);
+    }
+}
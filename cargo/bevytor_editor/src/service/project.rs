@@ -3,6 +3,8 @@ use crate::{bail, World};
 use bevy::asset::{FileAssetIo, HandleId};
 use bevy::prelude::*;
 use bevy::render::camera::{CameraProjection, Projection};
+use bevytor_core::project::ProjectState;
+pub use bevytor_core::project::{Project, ProjectDescription};
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ffi::OsString;
@@ -15,39 +17,51 @@ const INITIAL_TEMPLATE_SCENE_PATH: &str = "resources/initial.scn.ron";
 const INITIAL_TEMPLATE_ASSET_PATH: &str = "resources/initial.asset.ron";
 const INITIAL_ASSETS_PATH: &str = "resources/assets";
 
-#[derive(Default, Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
-pub struct ProjectDescription {
-    pub name: String,
-    pub path: OsString,
+/// Kind of a file in the assets folder, based on its extension.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AssetFileKind {
+    Gltf,
+    Image,
+    Audio,
+    Font,
+    Other,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ProjectState {
-    pub scene_file: String,
-    pub asset_file: String,
-    pub assets_folder: String,
-    pub script_enabled: bool,
-}
-
-#[derive(Default, Serialize, Deserialize, Clone)]
-pub struct Project {
-    pub project_description: ProjectDescription,
-    pub project_state: ProjectState,
-}
+impl AssetFileKind {
+    pub fn of(file: &str) -> Self {
+        let extension = file.rsplit_once('.').map_or("", |(_, e)| e).to_lowercase();
+        match extension.as_str() {
+            "gltf" | "glb" => Self::Gltf,
+            "png" | "jpg" | "jpeg" | "ktx2" | "hdr" | "tga" | "bmp" | "dds" => Self::Image,
+            "ogg" | "wav" | "mp3" | "flac" => Self::Audio,
+            "ttf" | "otf" => Self::Font,
+            _ => Self::Other,
+        }
+    }
 
-impl Default for ProjectState {
-    fn default() -> Self {
-        Self {
-            scene_file: "initial.scn.ron".to_string(),
-            asset_file: "initial.asset.ron".to_string(),
-            assets_folder: "assets".to_string(),
-            script_enabled: false,
+    pub fn group_name(&self) -> &'static str {
+        match self {
+            Self::Gltf => "glTF files",
+            Self::Image => "Images",
+            Self::Audio => "Audio",
+            Self::Font => "Fonts",
+            Self::Other => "Other files",
         }
     }
 }
 
-impl Project {
-    pub fn verify_new(path: OsString) -> EResult<()> {
+/// Project operations that touch the file system, the format itself is in `bevytor_core`.
+pub trait ProjectFiles: Sized {
+    fn verify_new(path: OsString) -> EResult<()>;
+    fn verify_existing(path: OsString) -> EResult<()>;
+    fn generate(description: ProjectDescription) -> EResult<Self>;
+    fn add_script_module(&mut self, module: &str) -> EResult<()>;
+    fn asset_files(&self, extensions: &[&str]) -> EResult<Vec<String>>;
+    fn load(description: ProjectDescription) -> EResult<Self>;
+}
+
+impl ProjectFiles for Project {
+    fn verify_new(path: OsString) -> EResult<()> {
         let project_path = Path::new(path.as_os_str());
         if project_path.exists() {
             if !project_path.is_dir() {
@@ -67,7 +81,7 @@ impl Project {
         Ok(())
     }
 
-    pub fn verify_existing(path: OsString) -> EResult<()> {
+    fn verify_existing(path: OsString) -> EResult<()> {
         let project_path = Path::new(path.as_os_str());
         if project_path.exists() {
             if !project_path.is_dir() {
@@ -85,7 +99,7 @@ impl Project {
         Ok(())
     }
 
-    pub fn generate(description: ProjectDescription) -> EResult<Project> {
+    fn generate(description: ProjectDescription) -> EResult<Project> {
         let project = Project {
             project_description: description.clone(),
             project_state: ProjectState::default(),
@@ -114,6 +128,11 @@ impl Project {
             bail!("PROJECT::BUILD::CANNOT_COPY_TEMPLATE", e);
         }
 
+        let initial_assets_path = Path::new(EDITOR_ROOT_FOLDER_PATH).join(INITIAL_ASSETS_PATH);
+        if let Err(e) = copy_recursively(initial_assets_path, project.assets_path()) {
+            bail!("PROJECT::BUILD::CANNOT_COPY_ASSETS", e);
+        }
+
         if let Err(e) = fs::write(project_path.join("project.bv"), serialized) {
             bail!("PROJECT::BUILD::CANNOT_WRITE_TO_PROJECT_FILE", e);
         }
@@ -189,7 +208,61 @@ impl Project {
         Ok(project)
     }
 
-    pub fn load(description: ProjectDescription) -> EResult<Project> {
+    /// Adds a script crate to the project. Existing folder is used as is, otherwise a new crate
+    /// is created from the template, named after the folder.
+    fn add_script_module(&mut self, module: &str) -> EResult<()> {
+        if module.is_empty()
+            || !module
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            bail!("PROJECT::SCRIPT::INVALID_NAME", module);
+        }
+        let mut modules = self.script_modules();
+        if modules.iter().any(|existing| existing == module) {
+            bail!("PROJECT::SCRIPT::ALREADY_ADDED", module);
+        }
+
+        let script_path = self.script_path(module);
+        if !script_path.exists() {
+            let template_script_path = Path::new(EDITOR_ROOT_FOLDER_PATH)
+                .join(TEMPLATE_PROJECT_DIR_PATH)
+                .join("scripts");
+            if let Err(e) = copy_recursively(template_script_path, &script_path) {
+                bail!("PROJECT::SCRIPT::CANNOT_COPY_TEMPLATE", e);
+            }
+            let manifest_path = script_path.join("Cargo.toml");
+            let manifest = match fs::read_to_string(&manifest_path) {
+                Ok(result) => result,
+                Err(e) => bail!("PROJECT::SCRIPT::CANNOT_READ_MANIFEST", e),
+            };
+            let manifest =
+                manifest.replacen("name = \"scripts\"", &format!("name = \"{}\"", module), 1);
+            if let Err(e) = fs::write(&manifest_path, manifest) {
+                bail!("PROJECT::SCRIPT::CANNOT_WRITE_MANIFEST", e);
+            }
+        }
+
+        modules.push(module.to_string());
+        self.project_state.script_modules = modules;
+        self.project_state.script_enabled = true;
+        Ok(())
+    }
+
+    /// Lists files in the assets folder (recursively), relative to it and with `/` separators.
+    fn asset_files(&self, extensions: &[&str]) -> EResult<Vec<String>> {
+        let mut files = vec![];
+        if !self.assets_path().exists() {
+            return Ok(files);
+        }
+        if let Err(e) = list_files_recursively(&self.assets_path(), "", extensions, &mut files) {
+            bail!("PROJECT::ASSETS::CANNOT_READ_ASSETS_FOLDER", e);
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn load(description: ProjectDescription) -> EResult<Project> {
         if let Err(e) = Self::verify_existing(description.path.clone()) {
             bail!("PROJECT::LOAD::VERIFY_FAILED", e);
         }
@@ -282,6 +355,33 @@ fn setup_template_pbr_bundle(
 }
 */
 
+fn list_files_recursively(
+    path: &Path,
+    prefix: &str,
+    extensions: &[&str],
+    files: &mut Vec<String>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if entry.file_type()?.is_dir() {
+            list_files_recursively(&entry.path(), &relative, extensions, files)?;
+        } else if extensions.is_empty()
+            || extensions
+                .iter()
+                .any(|extension| relative.to_lowercase().ends_with(extension))
+        {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
 fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> io::Result<()> {
     fs::create_dir_all(&destination)?;
     for entry in fs::read_dir(source)? {
@@ -0,0 +1,96 @@
+use crate::core::AssetRef;
+use crate::modules::hierarchy::drag_source;
+use bevy::prelude::Entity;
+use bevy_egui::egui::{CollapsingHeader, Id, ScrollArea, Ui};
+
+pub struct AssetItem {
+    pub label: String,
+    pub asset: AssetRef,
+    // entities (with labels) that use the asset
+    pub users: Vec<(Entity, String)>,
+    // `None` if the asset cannot be pinned (ex.: file without a source)
+    pub pinned: Option<bool>,
+}
+
+pub struct AssetGroup {
+    pub name: String,
+    pub items: Vec<AssetItem>,
+}
+
+#[derive(Default)]
+pub struct AssetBrowserResponse {
+    pub dragged: Option<AssetRef>,
+    pub selected: Option<Entity>,
+    pub toggle_pin: Option<AssetRef>,
+    pub refresh: bool,
+}
+
+pub fn asset_browser(ui: &mut Ui, groups: &[AssetGroup]) -> AssetBrowserResponse {
+    let mut response = AssetBrowserResponse::default();
+
+    ui.horizontal(|ui| {
+        ui.label("Assets");
+        if ui
+            .button("⟲")
+            .on_hover_text("Rescan assets folder")
+            .clicked()
+        {
+            response.refresh = true;
+        }
+    });
+    ui.separator();
+
+    ScrollArea::vertical().show(ui, |ui| {
+        for group in groups {
+            CollapsingHeader::new(format!("{} ({})", group.name, group.items.len()))
+                .id_source(("asset_group", group.name.as_str()))
+                .show(ui, |ui| {
+                    for item in &group.items {
+                        asset_row(ui, item, &mut response);
+                    }
+                });
+        }
+    });
+
+    response
+}
+
+fn asset_row(ui: &mut Ui, item: &AssetItem, response: &mut AssetBrowserResponse) {
+    ui.horizontal(|ui| {
+        let id = Id::new(("asset", format!("{:?}", item.asset)));
+        let (dragged, _) = drag_source(ui, id, |ui| {
+            ui.label(item.label.as_str());
+        });
+        if dragged {
+            response.dragged = Some(item.asset.clone());
+        }
+
+        if let Some(pinned) = item.pinned {
+            let hover_text = if pinned {
+                "Pinned, kept when unused assets are cleaned up"
+            } else {
+                "Pin to keep when unused assets are cleaned up"
+            };
+            if ui
+                .selectable_label(pinned, "📌")
+                .on_hover_text(hover_text)
+                .clicked()
+            {
+                response.toggle_pin = Some(item.asset.clone());
+            }
+        }
+
+        if item.users.is_empty() {
+            ui.weak("unused");
+        } else {
+            ui.menu_button(format!("👥 {}", item.users.len()), |ui| {
+                for (entity, label) in &item.users {
+                    if ui.button(label).clicked() {
+                        response.selected = Some(*entity);
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+    });
+}
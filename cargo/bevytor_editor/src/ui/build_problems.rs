@@ -0,0 +1,26 @@
+use crate::logs::Level;
+use crate::scripts::BuildProblem;
+use bevy_egui::egui::{CollapsingHeader, ScrollArea, Ui};
+
+/// Compiler errors and warnings of the last script builds, rendered text is shown when expanded.
+pub fn build_problems_ui(ui: &mut Ui, problems: &[&BuildProblem]) {
+    ui.label(format!("Build problems ({})", problems.len()));
+    ScrollArea::vertical()
+        .id_source("build_problems")
+        .max_height(200.)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            for (index, problem) in problems.iter().enumerate() {
+                let icon = match problem.level {
+                    Level::Error | Level::Fatal => "⛔",
+                    Level::Warn => "⚠",
+                    _ => "ℹ",
+                };
+                CollapsingHeader::new(format!("{} {}", icon, problem))
+                    .id_source(index)
+                    .show(ui, |ui| {
+                        ui.monospace(problem.rendered.as_str());
+                    });
+            }
+        });
+}
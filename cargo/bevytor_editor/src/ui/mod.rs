@@ -1,2 +1,3 @@
+pub(crate) mod assets;
+pub(crate) mod build_problems;
 pub(crate) mod project;
-pub(crate) mod file_explorer;
\ No newline at end of file
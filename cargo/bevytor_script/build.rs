@@ -0,0 +1,45 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+// versions are compiled into the crate, so editor and scripts can compare them, see
+// `ScriptMetadata`
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=BEVYTOR_RUSTC_VERSION={}", rustc_version);
+
+    // lock file of the crate that is being built (editor or script), found above the target dir
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap_or_default());
+    let lock_path = out_dir
+        .ancestors()
+        .map(|folder| folder.join("Cargo.lock"))
+        .find(|path| path.exists());
+    let bevy_version = lock_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|lock| locked_version(&lock, "bevy"))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=BEVYTOR_BEVY_VERSION={}", bevy_version);
+    if let Some(lock_path) = lock_path {
+        println!("cargo:rerun-if-changed={}", lock_path.display());
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name_line = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == name_line {
+            let version = lines.next()?.trim().strip_prefix("version = ")?;
+            return Some(version.trim_matches('"').to_string());
+        }
+    }
+    None
+}
@@ -1,8 +1,14 @@
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use bevy::reflect::{GetTypeRegistration, TypeRegistration};
+use bevy::window::PrimaryWindow;
 use std::any::{Any, TypeId};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::ffi::{c_char, CString};
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
 
 pub type Definition = (
     TypeRegistration,
@@ -14,12 +20,114 @@ pub type Definition = (
 pub trait Script: Any + Send + Sync {
     fn name(&self) -> &'static str;
     fn start(&self, world: &mut World);
-    fn run(&self, world: &mut World, input: &Input<KeyCode>);
+    fn run(&self, world: &mut World, context: &ScriptContext);
     fn init(&self, world: &mut World) -> Vec<Definition>;
+
+    /// Called at a fixed timestep while playing, see `FixedTime`.
+    fn fixed_run(&self, _world: &mut World, _context: &ScriptContext) {}
+    /// Called when playing is paused, the context is `Paused`.
+    fn on_pause(&self, _world: &mut World, _context: &ScriptContext) {}
+    fn on_resume(&self, _world: &mut World) {}
+    /// Called before the world is reset to the state before playing, the context is `Editing`.
+    fn on_stop(&self, _world: &mut World, _context: &ScriptContext) {}
+    /// Called before the script's components are removed and the library is closed.
+    fn on_unload(&self, _world: &mut World) {}
+    /// Called on the outgoing version, before its components are replaced.
+    fn on_before_reload(&self, _world: &mut World) {}
+    /// Called on the new version, after the components were replaced.
+    fn on_after_reload(&self, _world: &mut World) {}
+}
+
+/// Whether the scene is being played, a standalone runtime is always `Playing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayState {
+    Editing,
+    Playing,
+    Paused,
+}
+
+/// Input and time of the current frame, passed to `Script::run` and `Script::fixed_run`.
+/// Resources are copied once per frame, so scripts can still borrow the world mutably.
+#[derive(Resource)]
+pub struct ScriptContext {
+    pub keyboard: Input<KeyCode>,
+    pub mouse_buttons: Input<MouseButton>,
+    // in logical pixels of the primary window
+    pub cursor_position: Option<Vec2>,
+    pub touches: Touches,
+    pub gamepads: Vec<Gamepad>,
+    pub gamepad_buttons: Input<GamepadButton>,
+    gamepad_axes: HashMap<GamepadAxis, f32>,
+    pub time: Time,
+    pub play_state: PlayState,
+}
+
+impl ScriptContext {
+    /// Missing resources (ex.: app without a window) are left empty.
+    pub fn from_world(world: &mut World, play_state: PlayState) -> Self {
+        let cursor_position = world
+            .query_filtered::<&Window, With<PrimaryWindow>>()
+            .get_single(world)
+            .ok()
+            .and_then(|window| window.cursor_position());
+        let gamepads = world
+            .get_resource::<Gamepads>()
+            .map_or(vec![], |gamepads| gamepads.iter().collect());
+        let mut gamepad_axes = HashMap::new();
+        if let Some(axes) = world.get_resource::<Axis<GamepadAxis>>() {
+            for gamepad in gamepads.iter() {
+                for axis_type in [
+                    GamepadAxisType::LeftStickX,
+                    GamepadAxisType::LeftStickY,
+                    GamepadAxisType::LeftZ,
+                    GamepadAxisType::RightStickX,
+                    GamepadAxisType::RightStickY,
+                    GamepadAxisType::RightZ,
+                ] {
+                    let axis = GamepadAxis::new(*gamepad, axis_type);
+                    if let Some(value) = axes.get(axis) {
+                        gamepad_axes.insert(axis, value);
+                    }
+                }
+            }
+        }
+
+        Self {
+            keyboard: cloned_resource(world),
+            mouse_buttons: cloned_resource(world),
+            cursor_position,
+            touches: cloned_resource(world),
+            gamepads,
+            gamepad_buttons: cloned_resource(world),
+            gamepad_axes,
+            time: cloned_resource(world),
+            play_state,
+        }
+    }
+
+    /// Value of a standard axis in `[-1.0, 1.0]`, `0.0` if the gamepad is not connected.
+    pub fn gamepad_axis(&self, gamepad: Gamepad, axis_type: GamepadAxisType) -> f32 {
+        self.gamepad_axes
+            .get(&GamepadAxis::new(gamepad, axis_type))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.time.delta_seconds()
+    }
+}
+
+fn cloned_resource<R: Resource + Clone + Default>(world: &World) -> R {
+    world.get_resource::<R>().cloned().unwrap_or_default()
 }
 
 pub type CreateScript = unsafe fn() -> *mut dyn Script;
 
+/// Returns `ScriptMetadata::raw` of the library. Uses the C ABI, as it is called before the
+/// library is known to be compatible.
+pub type GetScriptMetadata = unsafe extern "C" fn() -> *const c_char;
+
 #[macro_export]
 macro_rules! declare_script {
     ($plugin_type:ty, $constructor:path) => {
@@ -31,9 +139,95 @@ macro_rules! declare_script {
             let boxed: Box<dyn $crate::Script> = Box::new(object);
             Box::into_raw(boxed)
         }
+
+        #[no_mangle]
+        pub extern "C" fn _script_metadata() -> *const std::os::raw::c_char {
+            $crate::script_metadata()
+        }
     };
 }
 
+/// Versions a library was built with. Script and editor share Rust types, so a library can only
+/// be loaded by an editor built with the same compiler and crates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptMetadata {
+    pub rustc_version: String,
+    pub bevy_version: String,
+    pub script_version: String,
+    // hash of `TypeId`s of types passed between editor and script
+    pub type_hash: u64,
+}
+
+impl ScriptMetadata {
+    pub fn current() -> Self {
+        let mut hasher = DefaultHasher::new();
+        TypeId::of::<World>().hash(&mut hasher);
+        TypeId::of::<Entity>().hash(&mut hasher);
+        TypeId::of::<AppTypeRegistry>().hash(&mut hasher);
+        TypeId::of::<Input<KeyCode>>().hash(&mut hasher);
+        TypeId::of::<ScriptContext>().hash(&mut hasher);
+        TypeId::of::<Transform>().hash(&mut hasher);
+        TypeId::of::<ComponentRegistry>().hash(&mut hasher);
+        TypeId::of::<dyn Script>().hash(&mut hasher);
+        Self {
+            rustc_version: env!("BEVYTOR_RUSTC_VERSION").to_string(),
+            bevy_version: env!("BEVYTOR_BEVY_VERSION").to_string(),
+            script_version: env!("CARGO_PKG_VERSION").to_string(),
+            type_hash: hasher.finish(),
+        }
+    }
+
+    /// One `key=value` per line.
+    pub fn raw(&self) -> String {
+        format!(
+            "rustc={}\nbevy={}\nbevytor_script={}\ntypes={:x}",
+            self.rustc_version, self.bevy_version, self.script_version, self.type_hash
+        )
+    }
+
+    pub fn from_raw(raw: &str) -> Option<Self> {
+        let values = raw
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .collect::<HashMap<_, _>>();
+        Some(Self {
+            rustc_version: values.get("rustc")?.to_string(),
+            bevy_version: values.get("bevy")?.to_string(),
+            script_version: values.get("bevytor_script")?.to_string(),
+            type_hash: u64::from_str_radix(values.get("types")?, 16).ok()?,
+        })
+    }
+
+    /// Describes the first difference to `other`, if any.
+    pub fn mismatch(&self, other: &ScriptMetadata) -> Option<String> {
+        let versions = [
+            ("rustc", &self.rustc_version, &other.rustc_version),
+            ("bevy", &self.bevy_version, &other.bevy_version),
+            (
+                "bevytor_script",
+                &self.script_version,
+                &other.script_version,
+            ),
+        ];
+        for (name, version, other_version) in versions {
+            if version != other_version {
+                return Some(format!("{} {} != {}", name, version, other_version));
+            }
+        }
+        if self.type_hash != other.type_hash {
+            return Some("shared types differ (different crate sources or features)".to_string());
+        }
+        None
+    }
+}
+
+/// Metadata of the calling crate as a C string, see `declare_script`.
+pub fn script_metadata() -> *const c_char {
+    static RAW: OnceLock<CString> = OnceLock::new();
+    RAW.get_or_init(|| CString::new(ScriptMetadata::current().raw()).unwrap_or_default())
+        .as_ptr()
+}
+
 #[derive(Resource, Default)]
 pub struct ComponentRegistry {
     pub reg: HashMap<TypeId, (String, Box<fn(&mut EntityCommands) -> ()>)>,
@@ -62,6 +256,64 @@ impl ComponentRegistry {
     //}
 }
 
+/// Schedule of the systems registered by scripts, run every frame while playing.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScriptUpdate;
+
+/// Each named script system is in its own set, so systems can be ordered against each other:
+/// `.after(ScriptSystemSet("movement".to_string()))`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScriptSystemSet(pub String);
+
+pub struct ScriptSystem {
+    pub name: String,
+    add: Box<dyn Fn(&mut Schedule) + Send + Sync>,
+}
+
+impl ScriptSystem {
+    pub fn add_to(&self, schedule: &mut Schedule) {
+        (self.add)(schedule);
+    }
+}
+
+/// Systems registered by a script in its `init`, taken by the editor after the call.
+#[derive(Resource, Default)]
+pub struct ScriptSystems {
+    systems: Vec<ScriptSystem>,
+}
+
+impl ScriptSystems {
+    /// `systems` is called each time the schedule is rebuilt, ex.: `|| movement.run_if(...)`.
+    pub fn add<M: 'static, S: IntoSystemConfigs<M> + 'static>(
+        &mut self,
+        name: impl ToString,
+        systems: impl Fn() -> S + Send + Sync + 'static,
+    ) {
+        let name = name.to_string();
+        let set = ScriptSystemSet(name.clone());
+        self.systems.push(ScriptSystem {
+            name,
+            add: Box::new(move |schedule| {
+                schedule.add_systems(systems().in_set(set.clone()));
+            }),
+        });
+    }
+
+    pub fn take(&mut self) -> Vec<ScriptSystem> {
+        std::mem::take(&mut self.systems)
+    }
+}
+
+pub fn register_system<M: 'static, S: IntoSystemConfigs<M> + 'static>(
+    world: &mut World,
+    name: impl ToString,
+    systems: impl Fn() -> S + Send + Sync + 'static,
+) {
+    world
+        .get_resource_or_insert_with(ScriptSystems::default)
+        .add(name, systems);
+}
+
 pub fn register_component<T>(world: &mut World) -> Definition
 where
     T: Reflect + Default + Component + GetTypeRegistration,
@@ -89,3 +341,34 @@ where
 
     (registration, component, serialize, deserialize)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_round_trips_and_reports_mismatches() {
+        let current = ScriptMetadata::current();
+        assert_eq!(
+            ScriptMetadata::from_raw(&current.raw()),
+            Some(current.clone())
+        );
+        assert_eq!(current.mismatch(&current), None);
+
+        let other_bevy = ScriptMetadata {
+            bevy_version: "0.10.1".to_string(),
+            ..current.clone()
+        };
+        assert_eq!(
+            other_bevy.mismatch(&current),
+            Some(format!("bevy 0.10.1 != {}", current.bevy_version))
+        );
+        let other_types = ScriptMetadata {
+            type_hash: current.type_hash.wrapping_add(1),
+            ..current.clone()
+        };
+        assert!(other_types.mismatch(&current).is_some());
+
+        assert_eq!(ScriptMetadata::from_raw("rustc=1.72.0\nbevy=0.11.3"), None);
+    }
+}
@@ -35,6 +35,16 @@ const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
 
 fn main() {
+    #[cfg(feature = "capture")]
+    if let Some(project_path) = std::env::args().nth(1) {
+        App::new()
+            .add_plugins(DefaultPlugins)
+            .add_systems(Startup, init_scene)
+            .add_plugins(bevytor::CapturePlugin::new("Jumpy bird", project_path))
+            .run();
+        return;
+    }
+
     App::new()
         .add_plugins(DefaultPlugins)
         .add_state::<AppState>()
@@ -0,0 +1,255 @@
+use bevy::app::AppExit;
+use bevy::asset::{Asset, AssetPath, FileAssetIo, HandleId};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::window::PrimaryWindow;
+use bevytor_core::material::MaterialData;
+use bevytor_core::mesh::MeshData;
+use bevytor_core::project::{
+    AssetSource, AssetSourceType, Project, ProjectDescription, ProjectState,
+};
+use bevytor_core::scene::fix_serialized_rect;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+const EDITOR_TEMPLATE_PROJECT_DIR_PATH: &str = "cargo/bevytor_editor/resources/project_template";
+
+/// Captures the world as it is after the `Startup` schedule and writes it as a bevytor project,
+/// then exits the app. Add it instead of the gameplay systems of an existing app:
+///
+/// ```ignore
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_systems(Startup, init_scene)
+///     .add_plugins(CapturePlugin::new("My game", "../my-game-project"))
+///     .run();
+/// ```
+///
+/// Meshes and standard materials created with `Assets::add` are stored as asset sources, meshes
+/// as raw geometry. Textures of the materials are copied to the project's assets folder.
+pub struct CapturePlugin {
+    pub name: String,
+    pub project_path: PathBuf,
+}
+
+impl CapturePlugin {
+    pub fn new(name: impl ToString, project_path: impl AsRef<Path>) -> Self {
+        Self {
+            name: name.to_string(),
+            project_path: project_path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+struct CaptureTarget {
+    name: String,
+    project_path: PathBuf,
+}
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CaptureTarget {
+            name: self.name.clone(),
+            project_path: self.project_path.clone(),
+        })
+        .add_systems(Update, capture_scene);
+    }
+}
+
+fn capture_scene(world: &mut World) {
+    let target = world.resource::<CaptureTarget>().clone();
+    match write_project(world, &target) {
+        Ok(()) => info!("Captured scene to {:?}", target.project_path),
+        Err(e) => error!("Cannot capture scene to {:?}: {}", target.project_path, e),
+    }
+    world.send_event(AppExit);
+}
+
+fn write_project(world: &mut World, target: &CaptureTarget) -> Result<(), String> {
+    let project = Project {
+        project_description: ProjectDescription {
+            name: target.name.clone(),
+            path: target.project_path.clone().into_os_string(),
+        },
+        // the scripts template is copied below
+        project_state: ProjectState {
+            script_enabled: true,
+            script_modules: vec!["scripts".to_string()],
+            ..default()
+        },
+    };
+
+    let scene_serialized = serialize_scene(world)?;
+    let (asset_sources, texture_paths) = capture_asset_sources(world);
+    let asset_sources_serialized =
+        ron::ser::to_string_pretty(&asset_sources, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+    let project_serialized = serde_json::to_string(&project).map_err(|e| e.to_string())?;
+
+    let project_path = target.project_path.as_path();
+    let scenes_path = project_path.join("scenes");
+    let template_path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(EDITOR_TEMPLATE_PROJECT_DIR_PATH);
+
+    let assets_path = scenes_path.join(&project.project_state.assets_folder);
+    fs::create_dir_all(&assets_path).map_err(|e| e.to_string())?;
+    copy_textures(world, &texture_paths, &assets_path);
+    copy_recursively(template_path.join("scripts"), project_path.join("scripts"))
+        .map_err(|e| e.to_string())?;
+    fs::copy(
+        template_path.join("version.txt"),
+        project_path.join("version.txt"),
+    )
+    .map_err(|e| e.to_string())?;
+    fs::write(
+        scenes_path.join(&project.project_state.scene_file),
+        scene_serialized,
+    )
+    .map_err(|e| e.to_string())?;
+    fs::write(
+        scenes_path.join(&project.project_state.asset_file),
+        asset_sources_serialized,
+    )
+    .map_err(|e| e.to_string())?;
+    fs::write(project_path.join("project.bv"), project_serialized).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn serialize_scene(world: &World) -> Result<String, String> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+
+    let mut builder = DynamicSceneBuilder::from_world(world);
+    builder.deny_all_resources().extract_resources();
+    builder
+        .allow_all()
+        .deny::<ComputedVisibility>()
+        .deny::<Window>()
+        .deny::<PrimaryWindow>()
+        .extract_entities(world.iter_entities().map(|r| r.id()))
+        .remove_empty_entities();
+
+    let scene = builder.build();
+    let scene_serialized = scene
+        .serialize_ron(type_registry)
+        .map_err(|e| e.to_string())?;
+    Ok(fix_serialized_rect(scene_serialized))
+}
+
+/// Returns the asset sources and the paths of the textures they use.
+fn capture_asset_sources(world: &mut World) -> (Vec<AssetSource>, HashSet<AssetPath<'static>>) {
+    let mut asset_sources = vec![];
+    let mut texture_paths = HashSet::new();
+    capture_assets::<Mesh>(world, &mut asset_sources, |mesh, _| {
+        let Some(mesh_data) = MeshData::from_mesh(mesh) else {
+            return Err("only triangle meshes with positions are supported".to_string());
+        };
+        ron::to_string(&mesh_data).map_err(|e| e.to_string())
+    });
+    capture_assets::<StandardMaterial>(world, &mut asset_sources, |material, world| {
+        let asset_server = world.resource::<AssetServer>();
+        let material_data = MaterialData::from_material(material, |texture| {
+            let Some(path) = asset_server.get_handle_path(texture) else {
+                warn!(
+                    "Texture {:?} was not loaded from a file and is not captured",
+                    texture
+                );
+                return None;
+            };
+            let relative = path_to_string(&path);
+            texture_paths.insert(path.to_owned());
+            Some(relative)
+        });
+        ron::to_string(&material_data).map_err(|e| e.to_string())
+    });
+    (asset_sources, texture_paths)
+}
+
+fn capture_assets<T: Asset>(
+    world: &mut World,
+    asset_sources: &mut Vec<AssetSource>,
+    mut to_string: impl FnMut(&T, &World) -> Result<String, String>,
+) {
+    let handle_ids = world
+        .query::<&Handle<T>>()
+        .iter(world)
+        .map(|handle| handle.id())
+        .collect::<HashSet<_>>();
+
+    let assets = world.resource::<Assets<T>>();
+    for handle_id in handle_ids {
+        let HandleId::Id(_, uid) = handle_id else {
+            warn!(
+                "Asset {:?} was loaded from a file and is not captured, assign it in the editor",
+                handle_id
+            );
+            continue;
+        };
+        let Some(asset) = assets.get(&Handle::<T>::weak(handle_id)) else {
+            warn!("Asset {:?} does not exist", handle_id);
+            continue;
+        };
+        match to_string(asset, world) {
+            Ok(raw) => asset_sources.push(AssetSource {
+                source_type: AssetSourceType::AsString(raw),
+                type_uuid: T::TYPE_UUID.to_string(),
+                uid,
+                pinned: false,
+                name: None,
+            }),
+            Err(e) => warn!("Asset {:?} cannot be captured: {}", handle_id, e),
+        }
+    }
+}
+
+// same format as the editor's asset references, relative path with optional `#label`
+fn path_to_string(path: &AssetPath) -> String {
+    let relative = path
+        .path()
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    match path.label() {
+        Some(label) => format!("{}#{}", relative, label),
+        None => relative,
+    }
+}
+
+fn copy_textures(world: &World, texture_paths: &HashSet<AssetPath>, assets_path: &Path) {
+    let asset_server = world.resource::<AssetServer>();
+    let Some(asset_io) = asset_server.asset_io().downcast_ref::<FileAssetIo>() else {
+        warn!("Assets are not loaded from files, textures are not copied");
+        return;
+    };
+    // labeled textures (ex.: of a glTF file) are copied with their whole file
+    let files = texture_paths
+        .iter()
+        .map(|path| path.path())
+        .collect::<HashSet<_>>();
+    for file in files {
+        let destination = assets_path.join(file);
+        let copied = destination
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::copy(asset_io.root_path().join(file), &destination));
+        if let Err(e) = copied {
+            warn!("Cannot copy texture {:?}: {}", file, e);
+        }
+    }
+}
+
+fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> io::Result<()> {
+    fs::create_dir_all(&destination)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_recursively(entry.path(), destination.as_ref().join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), destination.as_ref().join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
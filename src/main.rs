@@ -1,39 +1,19 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
+use bevytor::CapturePlugin;
 
+// Captures the template scene as a bevytor project, path of the project is the first argument
 fn main() {
+    let project_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./template".to_string());
+
     App::new()
         .add_plugins(DefaultPlugins)
         .add_systems(Startup, setup_template_scene)
-        .add_systems(Update, save_scene)
+        .add_plugins(CapturePlugin::new("Template", project_path))
         .run();
 }
 
-fn save_scene(world: &mut World) {
-    // just to be sure
-    std::thread::sleep(std::time::Duration::from_secs(2));
-
-    let type_registry = world.resource::<AppTypeRegistry>();
-
-    let mut builder = DynamicSceneBuilder::from_world(world);
-    builder.deny_all_resources().extract_resources();
-    builder
-        .allow_all()
-        .deny::<ComputedVisibility>()
-        .deny::<Window>()
-        .deny::<PrimaryWindow>()
-        .extract_entities(world.iter_entities().map(|r| r.id()))
-        .remove_empty_entities();
-
-    let scene = builder.build();
-    println!("{} {}", scene.entities.len(), scene.resources.len());
-    let scene_serialized = scene.serialize_ron(type_registry).unwrap();
-
-    std::fs::write(std::path::Path::new("./initial.ron"), scene_serialized).unwrap();
-
-    println!("saved");
-}
-
 fn setup_template_scene(
     world: &mut World,
     //commands: &mut Commands,